@@ -0,0 +1,145 @@
+/*
+coroutine.rs: Holds the coroutines of RLisp. A coroutine evaluates a function step by step: yield suspends
+              the evaluation at any depth and resume continues it later where it stopped. The evaluator
+              (see eval.rs) runs the coroutine on its stack and keeps the part of the stack that belongs
+              to the coroutine here while it is suspended. The builtins coroutine, resume, yield and
+              make-generator (see stdlib.rs) are built on it.
+*/
+
+// load functionality from sibling modules
+use crate::env::RlEnv;
+use crate::eval::Pending;
+use crate::types::{RlErr, RlType};
+
+// load needed Rust modules
+use std::cell::RefCell;
+use std::fmt;
+
+/// A function that is evaluated step by step. resume passes values in, yield passes values out.
+pub struct Coroutine {
+    // the function of the coroutine and the environment of its creator, which is passed on to builtins
+    func: RlType,
+    env: RlEnv,
+    state: RefCell<State>,
+}
+
+/// The states of a coroutine
+enum State {
+    // the function was not called yet
+    New,
+    // the stack entries of the coroutine (see eval.rs) while it is suspended by yield
+    Suspended(Vec<Pending>),
+    // the coroutine is on the stack of the evaluator
+    Running,
+    // the function has returned (with the value) or failed (with #nil)
+    Done(RlType),
+}
+
+/// How resume continues a coroutine
+pub(crate) enum Resumed {
+    // call the function with the arguments (the first element is the function)
+    Start(Vec<RlType>, RlEnv),
+    // put the stack entries back and return the value to the yield that suspended the coroutine
+    Continue(Vec<Pending>, RlType),
+}
+
+/// Implement the Debug trait by hand, since the stack entries can't be printed
+impl fmt::Debug for Coroutine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Coroutine")
+    }
+}
+
+impl Coroutine {
+    /**
+        Creates a new coroutine that calls the given function when it is resumed the first time. The
+        function is not called yet.
+
+        Arguments:  func - the function the coroutine evaluates
+                    environment - the environment of the caller, it is passed on to builtins
+        Returns:    the new Coroutine
+    */
+    pub fn new(func: RlType, environment: RlEnv) -> Coroutine {
+        return Coroutine {
+            func,
+            env: environment,
+            state: RefCell::new(State::New),
+        };
+    }
+
+    /**
+        Checks if the coroutine was resumed already.
+
+        Returns:    true if the function of the coroutine was called
+    */
+    pub fn started(&self) -> bool {
+        return !matches!(*self.state.borrow(), State::New);
+    }
+
+    /**
+        Checks if the function of the coroutine has returned (or failed).
+
+        Returns:    true if the coroutine can't be resumed anymore
+    */
+    pub fn done(&self) -> bool {
+        return matches!(*self.state.borrow(), State::Done(_));
+    }
+
+    /**
+        Gets the result of the function of a finished coroutine.
+
+        Returns:    the value the function returned (#nil if it failed) or None if it is not finished
+    */
+    pub fn result(&self) -> Option<RlType> {
+        return match &*self.state.borrow() {
+            State::Done(value) => Some(value.clone()),
+            _ => None,
+        };
+    }
+
+    /**
+        Marks the coroutine as running and tells the evaluator how to continue it. The first resume calls
+        the function with the arguments, the following ones return the first argument (or #nil) from the
+        yield the coroutine was suspended at.
+
+        Arguments:  args - the values passed into the coroutine
+        Returns:    how to continue the coroutine or an Error if the coroutine is running already or
+                    has finished
+    */
+    pub(crate) fn resume(&self, args: Vec<RlType>) -> Result<Resumed, RlErr> {
+        return match self.state.replace(State::Running) {
+            State::New => {
+                let mut call = vec![self.func.clone()];
+                call.extend(args);
+                Ok(Resumed::Start(call, self.env.clone()))
+            }
+            State::Suspended(frames) => {
+                let value = args.into_iter().next().unwrap_or(RlType::Nil);
+                Ok(Resumed::Continue(frames, value))
+            }
+            State::Running => Err(RlErr::State("a running coroutine can't be resumed".to_string())),
+            done => {
+                self.state.replace(done);
+                Err(RlErr::State("a finished coroutine can't be resumed".to_string()))
+            }
+        };
+    }
+
+    /**
+        Keeps the stack entries of the coroutine, which was suspended by yield.
+
+        Arguments:  frames - the stack entries of the coroutine
+    */
+    pub(crate) fn suspend(&self, frames: Vec<Pending>) {
+        self.state.replace(State::Suspended(frames));
+    }
+
+    /**
+        Marks the coroutine as finished.
+
+        Arguments:  result - the value the function returned, #nil if it failed
+    */
+    pub(crate) fn finish(&self, result: RlType) {
+        self.state.replace(State::Done(result));
+    }
+}
@@ -5,11 +5,13 @@ env.rs: This module contains the definition of an environment in RLisp. An envir
  */
 
 // load functionality from sibling modules
+use crate::eval::eval;
+use crate::printer::print_str;
 use crate::stdlib::core;
-use crate::types::{error, RlErr, RlReturn, RlType};
+use crate::types::{builtin, builtin_with_env, check_arity, list_from_vec, type_error, RlErr, RlReturn, RlType};
 // load needed Rust modules
-use std::collections::HashMap;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
 
 /// Define RlEnv Type for convenience: the Env is wrapped in a Rc object(Smart Pointer),
@@ -56,40 +58,233 @@ pub fn new_env(outer: Option<RlEnv>) -> RlEnv {
 /**
     creates a new environment and directly set given key value pairs in the environment. Then
     returns the environment or an RlError if anything fails.
+    Besides plain symbols, the parameter list can contain the markers &optional, &rest and &key (see
+    parse_params). Missing optional and keyword arguments get their default value, which is evaluated
+    in the new environment (so it can use the parameters in front of it), or #nil.
 
     Arguments:  outer - optional pointer to outer environment of new environment
+                name - the name of the function or macro (used in error messages)
                 names - a list of symbol-names that will be mapped to expressions in new environment
                 targets - the list of expressions matching the symbol-names in names
     Returns:    The new environment with the bindings or an Error
 */
 pub fn new_env_bound(
     outer: Option<RlEnv>,
-    names: Vec<RlType>,
+    name: &str,
+    names: &[RlType],
     targets: Vec<RlType>,
 ) -> Result<RlEnv, RlErr> {
     // create new environment using new_env()
     let env = new_env(outer);
-    // check if lengths of lists are matching
-    return if names.len() != targets.len() {
-        Err(error("Error: Number of arguments are not matching!"))
-    } else {
+    // a parameter list without markers just contains the required parameters
+    if !names.iter().any(is_marker) {
+        // check if lengths of lists are matching
+        check_arity(name, targets.len(), names.len(), Some(names.len()))?;
         // iterate through names
-        for (i, name) in names.iter().enumerate() {
-            match name {
+        for (i, param) in names.iter().enumerate() {
+            match param {
                 // if name is a valid Symbol, set the symbol-name to matching expression in targets
                 RlType::Symbol(s) => set(&env, s.to_string(), targets[i].clone()),
-                _ => {
-                    return Err(error(
-                        "Error: In self defined functions, Parameter names must be Symbols",
-                    ))
-                }
+                _ => return Err(type_error("a symbol as parameter name", param)),
             }
         }
         // Return new environment
-        Ok(env.clone())
+        return Ok(env);
+    }
+    let params = parse_params(names)?;
+    let positional = params.required.len() + params.optional.len();
+    // with rest or keyword parameters there is no upper limit for the number of arguments
+    let max = match (params.rest, params.keys.is_empty()) {
+        (None, true) => Some(positional),
+        _ => None,
+    };
+    check_arity(name, targets.len(), params.required.len(), max)?;
+    // bind the required and optional parameters to the arguments in order
+    for (i, param) in params.required.iter().enumerate() {
+        set(&env, param.to_string(), targets[i].clone());
+    }
+    for (i, (param, default)) in params.optional.iter().enumerate() {
+        let value = match targets.get(params.required.len() + i) {
+            Some(value) => value.clone(),
+            None => default_value(*default, &env)?,
+        };
+        set(&env, param.to_string(), value);
+    }
+    // the rest parameter gets a list of the remaining arguments
+    let remaining = &targets[positional.min(targets.len())..];
+    if let Some(rest) = params.rest {
+        set(&env, rest.to_string(), list_from_vec(remaining.to_vec()));
+    }
+    if params.keys.is_empty() {
+        return Ok(env);
+    }
+    // the remaining arguments are pairs of a keyword (:name) and a value
+    let mut values: Vec<Option<RlType>> = vec![None; params.keys.len()];
+    for pair in remaining.chunks(2) {
+        let position = match &pair[0] {
+            RlType::Symbol(keyword) => params.keys.iter().position(|(key, _)| keyword == &format!(":{}", key)),
+            _ => None,
+        };
+        match (position, pair.get(1)) {
+            (Some(position), Some(value)) => values[position] = Some(value.clone()),
+            (Some(_), None) => {
+                return Err(RlErr::Syntax(format!(
+                    "{} got no value for the keyword {}",
+                    name,
+                    print_str(pair[0].clone())
+                )))
+            }
+            (None, _) => {
+                return Err(RlErr::Syntax(format!(
+                    "{} has no keyword parameter {}",
+                    name,
+                    print_str(pair[0].clone())
+                )))
+            }
+        }
+    }
+    for ((param, default), value) in params.keys.iter().zip(values) {
+        let value = match value {
+            Some(value) => value,
+            None => default_value(*default, &env)?,
+        };
+        set(&env, param.to_string(), value);
+    }
+    return Ok(env);
+}
+
+/// The parameters of a function or macro, grouped by their kind. Optional and keyword parameters can
+/// have an expression for their default value.
+struct Params<'a> {
+    required: Vec<&'a str>,
+    optional: Vec<(&'a str, Option<&'a RlType>)>,
+    rest: Option<&'a str>,
+    keys: Vec<(&'a str, Option<&'a RlType>)>,
+}
+
+/// The parts of a parameter list, they have to appear in this order
+#[derive(PartialEq, PartialOrd, Clone, Copy)]
+enum Section {
+    Required,
+    Optional,
+    Rest,
+    Key,
+}
+
+/**
+    Checks if a parameter is one of the markers &optional, &rest or &key
+
+    Arguments:  param - the parameter to check
+    Returns:    true if it is a marker
+*/
+fn is_marker(param: &RlType) -> bool {
+    return matches!(param, RlType::Symbol(s) if s == "&optional" || s == "&rest" || s == "&key");
+}
+
+/**
+    Groups a parameter list of the form (required ... &optional optional ... &rest rest &key key ...).
+    All parts are optional, but they have to be in this order. An optional or keyword parameter is
+    either a symbol or a (symbol default-expression) list, &rest is followed by exactly one symbol.
+
+    Arguments:  names - the parameter list
+    Returns:    the grouped parameters or an Error if the parameter list is invalid
+*/
+fn parse_params(names: &[RlType]) -> Result<Params<'_>, RlErr> {
+    let mut params = Params {
+        required: vec![],
+        optional: vec![],
+        rest: None,
+        keys: vec![],
+    };
+    let mut section = Section::Required;
+    for param in names.iter() {
+        // a marker starts the next section
+        let next = match param {
+            RlType::Symbol(s) if s == "&optional" => Some(Section::Optional),
+            RlType::Symbol(s) if s == "&rest" => Some(Section::Rest),
+            RlType::Symbol(s) if s == "&key" => Some(Section::Key),
+            _ => None,
+        };
+        if let Some(next) = next {
+            if next <= section || (section == Section::Rest && params.rest.is_none()) {
+                return Err(RlErr::Syntax(format!("{} is not allowed here", print_str(param.clone()))));
+            }
+            section = next;
+            continue;
+        }
+        match (section, param) {
+            (Section::Required, RlType::Symbol(s)) => params.required.push(s),
+            (Section::Optional, RlType::Symbol(s)) => params.optional.push((s, None)),
+            (Section::Key, RlType::Symbol(s)) => params.keys.push((s, None)),
+            (Section::Rest, RlType::Symbol(s)) if params.rest.is_none() => params.rest = Some(s),
+            (Section::Rest, _) => return Err(RlErr::Syntax("&rest takes exactly one parameter".to_string())),
+            // optional and keyword parameters can have a default value
+            (Section::Optional | Section::Key, RlType::List(l, _)) if l.len() == 2 => match &l[0] {
+                RlType::Symbol(s) if section == Section::Optional => params.optional.push((s, Some(&l[1]))),
+                RlType::Symbol(s) => params.keys.push((s, Some(&l[1]))),
+                other => return Err(type_error("a symbol as parameter name", other)),
+            },
+            _ => return Err(type_error("a symbol as parameter name", param)),
+        }
+    }
+    if section == Section::Rest && params.rest.is_none() {
+        return Err(RlErr::Syntax("&rest takes exactly one parameter".to_string()));
+    }
+    return Ok(params);
+}
+
+/**
+    Evaluates the default value of an optional or keyword parameter
+
+    Arguments:  default - the expression of the default value, None if there is none
+                env - the environment of the function call
+    Returns:    the value of the expression, #nil if there is no default value
+*/
+fn default_value(default: Option<&RlType>, env: &RlEnv) -> RlReturn {
+    return match default {
+        Some(expression) => eval(expression.clone(), env.clone()),
+        None => Ok(RlType::Nil),
     };
 }
 
+/**
+    Converts the parameter list of lambda or defmacro to the list that is stored in the function. A
+    dotted list (a b . rest) and a single symbol args (all arguments as list) are converted to lists
+    with &rest: (a b &rest rest) and (&rest args).
+
+    Arguments:  params - the parameter list as written in the source code
+    Returns:    the parameter list or an Error if it is invalid
+*/
+pub(crate) fn parameter_list(params: &RlType) -> Result<Vec<RlType>, RlErr> {
+    let mut names = vec![];
+    let mut rest = params.clone();
+    loop {
+        match &rest {
+            RlType::Pair(car, cdr) => {
+                names.push(car.borrow().clone());
+                let next = cdr.borrow().clone();
+                rest = next;
+            }
+            RlType::List(l, _) => {
+                names.extend(l.iter().cloned());
+                break;
+            }
+            // the tail of a dotted list (or a single symbol) takes the remaining arguments
+            RlType::Symbol(_) => {
+                names.push(RlType::Symbol("&rest".to_string()));
+                names.push(rest);
+                break;
+            }
+            other => return Err(type_error("a list of parameters", other)),
+        }
+    }
+    // check if the parameter list is valid
+    if names.iter().any(is_marker) {
+        parse_params(&names)?;
+    }
+    return Ok(names);
+}
+
 /**
     Takes an environment, a symbol-name and an expression. Associate the symbol-name with the given
     expression in the given environment.
@@ -102,6 +297,70 @@ pub(crate) fn set(environment: &RlEnv, symbol: String, expr: RlType) {
     environment.env.borrow_mut().insert(symbol.clone(), expr.clone());
 }
 
+/**
+    Takes an environment, a symbol-name and an expression. Changes the existing mapping of the symbol
+    to the given expression. Like search, it starts with the most inner environment and proceeds with
+    the outer environments until the symbol-name is found, so closures can change the variables of
+    the environment they were created in.
+
+    Arguments:  environment - the environment to start the search in
+                symbol - the symbolname to change
+                expr - the new expression of the symbol
+    Returns:    Ok if the mapping was changed, an Error if the symbol is not defined
+*/
+pub(crate) fn assign(environment: &RlEnv, symbol: String, expr: RlType) -> Result<(), RlErr> {
+    // change the mapping if the symbol is defined in the current environment
+    if let Some(value) = environment.env.borrow_mut().get_mut(&symbol) {
+        *value = expr;
+        return Ok(());
+    }
+    // else continue with the outer environment
+    return match &environment.outer {
+        Some(outer) => assign(outer, symbol, expr),
+        None => Err(RlErr::UnboundSymbol(symbol)),
+    };
+}
+
+/**
+    Registers a Rust closure as builtin function in the given environment (usually the global one).
+    The closure can capture state, e.g. a counter or a handle to a database.
+
+    Arguments:  environment - the environment to register the builtin in
+                name - the symbol-name of the builtin
+                min_args - the minimal number of arguments
+                max_args - the maximal number of arguments, None if there is no upper limit
+                func - the closure that is called with the evaluated arguments
+*/
+pub fn register(
+    environment: &RlEnv,
+    name: &str,
+    min_args: usize,
+    max_args: Option<usize>,
+    func: impl Fn(&[RlType]) -> RlReturn + 'static,
+) {
+    set(environment, name.to_string(), builtin(name, min_args, max_args, func));
+}
+
+/**
+    Like register, but the closure gets the environment of the caller as second argument. So it can
+    call RLisp functions with eval::apply.
+
+    Arguments:  environment - the environment to register the builtin in
+                name - the symbol-name of the builtin
+                min_args - the minimal number of arguments
+                max_args - the maximal number of arguments, None if there is no upper limit
+                func - the closure that is called with the evaluated arguments and the caller's environment
+*/
+pub fn register_with_env(
+    environment: &RlEnv,
+    name: &str,
+    min_args: usize,
+    max_args: Option<usize>,
+    func: impl Fn(&[RlType], &RlEnv) -> RlReturn + 'static,
+) {
+    set(environment, name.to_string(), builtin_with_env(name, min_args, max_args, func));
+}
+
 /**
     Takes an environment and a symbol-name. Searches the environment for the symbol name.
     Start with the most inner environment and proceed with outer environments until symbol-name
@@ -119,7 +378,7 @@ pub fn search(environment: &RlEnv, key: String) -> RlReturn {
             // if there is an outer environment search in outer environment
             Some(x) => search(x, key),
             // if not we are certain that symbol is not defined
-            None => Err(error(&format!("Symbol {} not found", key))),
+            None => Err(RlErr::UnboundSymbol(key)),
         },
         // if symbol was found, return it's value
         Some(value) => Ok(value.clone()),
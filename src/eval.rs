@@ -4,16 +4,190 @@ eval.rs: holds the functionality of the evaluator of the Interpreter!
 The module "eval" is a submodule of "main" and contains the two functions eval and apply which are
 the core of the Interpreter. "eval" is the Interface to the Evaluator and is everything that needs
 to be called to evaluate an expression.
+
+The evaluator doesn't call itself to evaluate sub-expressions. The steps that wait for the value of a
+sub-expression are kept on an explicit stack instead (see Kont), so the depth of non-tail recursion in
+RLisp programs is not limited by the Rust stack. Every special form has its own handler function that
+returns the next step of the evaluation (see Step).
+
+Since the rest of an evaluation is data on the stack, call/cc can copy it into a continuation and
+calling the continuation can put it back, as often as it is called (see Continuation). The builtins that
+call other functions (map, apply, dynamic-wind, ...) are primitives of the evaluator for this reason
+(see apply_primitive): their calls are on the stack as well. Coroutines run on the stack, too: yield
+moves the entries of the coroutine from the stack into the coroutine and resume puts them back.
 */
 
 // load important functionality of other sibling-modules
-use super::env::{new_env, search, set, RlEnv};
-use super::types::{error, RlReturn, RlType};
-use crate::env::new_env_bound;
+use super::env::{assign, new_env, search, set, RlEnv};
+use super::types::{
+    check_arity, error_to_value, is_truthy, list_from_vec, list_to_vec, make_list, make_pair, to_data, type_error,
+    uncons, untraced, Frame, Location, Primitive, Promise, RlErr, RlReturn, RlType,
+};
+use crate::coroutine::{Coroutine, Resumed};
+use crate::env::{new_env_bound, parameter_list};
+use crate::stdlib::{list_elements, zip_lists};
 
 // load needed Rust modules
+use std::cell::{Cell, RefCell};
+use std::fmt;
 use std::rc::Rc;
 
+thread_local! {
+    // The pending steps of all running evaluations (innermost last). An evaluation that is started
+    // from Rust code (e.g. by a builtin calling apply) starts with a Kont::Barrier.
+    static STACK: RefCell<Vec<Pending>> = const { RefCell::new(Vec::new()) };
+    // The strict-boolean option of the interpreter that is evaluating right now (see truth).
+    static STRICT_BOOLEANS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The next step of an evaluation. The handlers of the special forms return it instead of evaluating
+/// sub-expressions themselves.
+enum Step {
+    // evaluate the expression in tail position: it replaces the current expression
+    Eval(RlType, RlEnv),
+    // push the continuation frame and evaluate the expression, its value is passed to the frame
+    Sub(Kont, RlType, RlEnv),
+    // call the function (the first element) with the arguments (the other elements)
+    Apply(Vec<RlType>, RlEnv),
+    // push the continuation frame and call the function, its result is passed to the frame
+    Call(Kont, Vec<RlType>, RlEnv),
+    // pass the value to the innermost continuation frame on the stack
+    Return(RlType),
+}
+
+/// A continuation frame: a step of the evaluation that waits for the value of a sub-expression. The
+/// forms are kept as the elements of their list. Continuations copy the frames, so a frame can be
+/// continued more than once.
+#[derive(Clone)]
+enum Kont {
+    // the start of an evaluation started from Rust code, the value is returned to the Rust code. The Rc
+    // identifies the evaluation
+    Barrier(Rc<()>),
+    // a call of a SelfDefinedFunc, shown in the backtrace of errors
+    Call(Frame),
+    // the elements of a function call that are evaluated already
+    Args {
+        call: Vec<RlType>,
+        values: Vec<RlType>,
+        env: RlEnv,
+    },
+    // the argument of eval, its value is evaluated as code
+    Code(RlEnv),
+    // the test of the clause at index of cond
+    Cond { form: Vec<RlType>, index: usize, env: RlEnv },
+    // the test of if
+    If { form: Vec<RlType>, env: RlEnv },
+    // the test of when or unless
+    When { form: Vec<RlType>, env: RlEnv },
+    // the argument at index of and or or
+    Logic { form: Vec<RlType>, index: usize, env: RlEnv },
+    // the key of case
+    Case { form: Vec<RlType>, env: RlEnv },
+    // the value of define
+    Define { key: String, env: RlEnv },
+    // the value of set!
+    Assign { key: String, env: RlEnv },
+    // the value of the next binding of a let form, env is the environment it is evaluated in
+    Let {
+        kind: LetKind,
+        bindings: Vec<(String, RlType)>,
+        values: Vec<RlType>,
+        body: RlType,
+        env: RlEnv,
+    },
+    // the expression at index of do
+    Do { form: Vec<RlType>, index: usize, env: RlEnv },
+    // the head of cons-stream
+    ConsStream { tail: RlType, env: RlEnv },
+    // the argument of macroexpand-1 (once) or macroexpand
+    Macroexpand { once: bool, env: RlEnv },
+    // the expression of try, errors are caught here (see unwind)
+    Try { name: String, handler: RlType, env: RlEnv },
+    // the before thunk of dynamic-wind, then the thunk is called
+    Enter { wind: Rc<Wind>, thunk: RlType },
+    // the thunk of dynamic-wind, then the after thunk is called
+    Wind(Rc<Wind>),
+    // the after thunk of dynamic-wind, then the value of the thunk is returned
+    Value(RlType),
+    // an after thunk that is called while the error unwinds the stack, then the error is raised again
+    Raise(RlErr),
+    // a call of the function of map, for-each, filter, remove, reduce, fold-left or fold-right
+    Each(Each),
+    // the expression of a promise that is forced
+    Force(Rc<RefCell<Promise>>),
+    // the bottom of a running coroutine: the entries above it belong to the coroutine
+    CoroutineEnd(Rc<Coroutine>),
+}
+
+/// The state of map, for-each, filter, remove, reduce, fold-left and fold-right: the function is called
+/// once for each entry of calls
+#[derive(Clone)]
+struct Each {
+    primitive: Primitive,
+    func: RlType,
+    // the elements of the lists the function is called with, in the order of the calls
+    calls: Rc<Vec<Vec<RlType>>>,
+    // the call that is running
+    index: usize,
+    // the collected elements of the result of map, filter and remove
+    results: Vec<RlType>,
+    // the accumulated value of reduce, fold-left and fold-right
+    acc: RlType,
+    env: RlEnv,
+}
+
+/// The thunks of a dynamic-wind. The after thunk is called whenever the stack entry of the thunk
+/// (Kont::Wind) is left, the before thunk whenever a continuation enters it again.
+struct Wind {
+    before: RlType,
+    after: RlType,
+    env: RlEnv,
+}
+
+/// A continuation created by call/cc: a copy of the stack entries of the evaluation call/cc was called
+/// in. Calling it replaces the entries of the evaluation with the copy (see throw).
+pub struct Continuation {
+    // the evaluation the continuation belongs to (see Kont::Barrier)
+    run: Rc<()>,
+    frames: Vec<Pending>,
+}
+
+/// Implement the Debug trait by hand, since the stack entries can't be printed
+impl fmt::Debug for Continuation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Continuation")
+    }
+}
+
+/// The kinds of let forms. They differ in the environment the values are evaluated in and in when
+/// the symbols are bound.
+#[derive(Clone)]
+enum LetKind {
+    // let: the values are evaluated in the outer environment and bound in a new one at the end
+    Parallel,
+    // named let: the values are the arguments of the first call of the loop function
+    Named {
+        name: String,
+        env: RlEnv,
+        params: Rc<Vec<RlType>>,
+    },
+    // let* and letrec*: each value is bound in the new environment right after it was evaluated
+    Sequential,
+    // letrec: the values are evaluated in the new environment and bound at the end
+    Recursive,
+}
+
+/// An entry of the stack: the continuation frame together with the location of the form that was
+/// evaluated when it was pushed
+#[derive(Clone)]
+pub(crate) struct Pending {
+    kont: Kont,
+    location: Option<Rc<Location>>,
+}
+
+/// The handler of a special form: it gets the elements of the form and the environment
+type SpecialForm = fn(Vec<RlType>, RlEnv) -> Result<Step, RlErr>;
+
 /**
     Is the core function of the Interpreter, it takes an AST and tries to evaluate it.
     1. Check if given AST is a List
@@ -28,168 +202,1719 @@ use std::rc::Rc;
         2.1 Symbols will be looked up in the environment
         2.2 Integers, Bool, Nil and Strings are self-evaluating
 
+    Tail Call Optimization: Expressions in tail position (the chosen cond branch, the body of let,
+    the last expression of do, the argument of eval and the body of a SelfDefinedFunc) replace the
+    current expression without pushing a continuation frame, so tail-recursive RLisp programs run in
+    constant space.
+
+    Every call of a SelfDefinedFunc is recorded on the stack while it is evaluated. If an error
+    occurs, the recorded calls and the location of the failing form are attached to it (see
+    RlErr::Traced).
+
     Arguments:  expression - Abstract Syntax Tree(AST) that represents the expression to evaluate
                 environment - the environment the expression is evaluated in
     Returns:    of type RlReturn - in case of an Error, is RLError otherwise the resulting AST (result of the whole evaluation)
 */
 pub fn eval(expression: RlType, environment: RlEnv) -> RlReturn {
-    match expression.clone() {
+    return run(Step::Eval(expression, environment));
+}
+
+/**
+    Runs an evaluation on the stack, starting with the given step, until its value is returned to the
+    barrier it pushed first. Errors unwind the stack up to the innermost try of this evaluation.
+
+    Arguments:  first - the first step of the evaluation
+    Returns:    the value of the evaluation or an Error
+*/
+fn run(first: Step) -> RlReturn {
+    // continuations that are captured in this evaluation refer to it with the Rc of the barrier
+    let id = Rc::new(());
+    push(Kont::Barrier(id.clone()), None);
+    // the location of the last evaluated form that has one, for the backtrace of errors
+    let mut location = None;
+    let mut step = Ok(first);
+    loop {
+        step = match step {
+            Ok(Step::Eval(expression, environment)) => eval_expression(expression, environment, &mut location),
+            Ok(Step::Sub(kont, expression, environment)) => {
+                // the sub-expression is evaluated on its own, the location is restored when it returns
+                push(kont, location.take());
+                eval_expression(expression, environment, &mut location)
+            }
+            Ok(Step::Apply(args, environment)) => apply_function(args, environment, &location),
+            Ok(Step::Call(kont, args, environment)) => {
+                push(kont, location.clone());
+                apply_function(args, environment, &location)
+            }
+            Ok(Step::Return(value)) => {
+                let pending = pop();
+                location = pending.location;
+                match pending.kont {
+                    Kont::Barrier(_) => return Ok(value),
+                    kont => return_to(kont, value),
+                }
+            }
+            // a continuation of this evaluation was called in an evaluation that was started from it
+            Err(RlErr::Escape { continuation, value }) if Rc::ptr_eq(&continuation.run, &id) => {
+                reinstate(&continuation).map(|_| Step::Return(value))
+            }
+            Err(err) => {
+                let err = attach_trace(err, location.take());
+                let (handler, handler_location) = unwind(err)?;
+                location = handler_location;
+                Ok(handler)
+            }
+        };
+    }
+}
+
+/**
+    Pushes a continuation frame on the stack.
+
+    Arguments:  kont - the continuation frame
+                location - the location of the form that was evaluated when it was pushed
+*/
+fn push(kont: Kont, location: Option<Rc<Location>>) {
+    STACK.with(|stack| stack.borrow_mut().push(Pending { kont, location }));
+}
+
+/**
+    Removes the innermost continuation frame from the stack. There is always one, since every
+    evaluation starts with a barrier and stops when it was removed.
+
+    Returns:    the continuation frame with the location that was stored with it
+*/
+fn pop() -> Pending {
+    return STACK.with(|stack| stack.borrow_mut().pop()).expect("the stack of the evaluator is empty");
+}
+
+/**
+    Records a call of a SelfDefinedFunc on the stack. A call in tail position replaces the call it is
+    made from, so the stack doesn't grow in tail-recursive loops: if the innermost frame is a call,
+    the current expression is the body of this call.
+
+    Arguments:  frame - the frame of the call
+*/
+fn push_call(frame: Frame) {
+    STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        if let Some(Pending { kont: Kont::Call(_), .. }) = stack.last() {
+            stack.pop();
+        }
+        let location = frame.location.clone();
+        stack.push(Pending {
+            kont: Kont::Call(frame),
+            location,
+        });
+    });
+}
+
+/**
+    Attaches the calls on the stack and the location of the failing form to an error, if it has no
+    calls attached yet. If the failing expression has no location (e.g. a symbol), the location of the
+    form it belongs to is used. An error that was caught and raised again keeps its calls.
+
+    Arguments:  err - the error that occurred
+                location - the location of the form whose evaluation failed, if known
+    Returns:    the error with the calls and the location attached
+*/
+fn attach_trace(err: RlErr, location: Option<Rc<Location>>) -> RlErr {
+    if let RlErr::Traced { location: Some(_), .. } | RlErr::Escape { .. } = err {
+        return err;
+    }
+    let (trace, location) = STACK.with(|stack| {
+        let stack = stack.borrow();
+        let trace: Vec<Frame> = stack
+            .iter()
+            .rev()
+            .filter_map(|pending| match &pending.kont {
+                Kont::Call(frame) => Some(frame.clone()),
+                _ => None,
+            })
+            .collect();
+        // the innermost form with a location, up to the try that catches the error. Beyond the
+        // barrier, the evaluation that started this one fills in the location
+        let location = location.or_else(|| {
+            stack
+                .iter()
+                .rev()
+                .take_while(|pending| !matches!(pending.kont, Kont::Try { .. } | Kont::Barrier(_)))
+                .find_map(|pending| pending.location.clone())
+        });
+        (trace, location)
+    });
+    return match err {
+        RlErr::Traced { error, trace, .. } => RlErr::Traced { error, trace, location },
+        err if trace.is_empty() && location.is_none() => err,
+        err => RlErr::Traced {
+            error: Box::new(err),
+            trace,
+            location,
+        },
+    };
+}
+
+/**
+    Removes the continuation frames of the current evaluation from the stack until a try is found that
+    catches the error. Continuations that are called are no errors, they pass all tries. If the thunk
+    of a dynamic-wind is left, its after thunk is called before the error is raised again.
+
+    Arguments:  err - the error
+    Returns:    the step that evaluates the handler of the try (or calls the after thunk) with the
+                location of its form, or the error if the evaluation has no try that catches it
+*/
+fn unwind(err: RlErr) -> Result<(Step, Option<Rc<Location>>), RlErr> {
+    loop {
+        let pending = pop();
+        match pending.kont {
+            Kont::Barrier(_) => return Err(err),
+            Kont::Try { name, handler, env } if !matches!(untraced(&err), RlErr::Escape { .. }) => {
+                // bind the error in a new environment and evaluate the handler (tail position)
+                let handler_env = new_env(Some(env));
+                set(&handler_env, name, error_to_value(err));
+                return Ok((Step::Eval(handler, handler_env), pending.location));
+            }
+            Kont::Wind(wind) => {
+                let call = vec![wind.after.clone()];
+                return Ok((Step::Call(Kont::Raise(err), call, wind.env.clone()), pending.location));
+            }
+            // the function of the coroutine failed, the error is raised by resume
+            Kont::CoroutineEnd(coroutine) => coroutine.finish(RlType::Nil),
+            _ => {}
+        }
+    }
+}
+
+/**
+    Finds the barrier of the innermost evaluation on the stack.
+
+    Arguments:  stack - the stack
+    Returns:    the index of the barrier
+*/
+fn barrier(stack: &[Pending]) -> usize {
+    return stack
+        .iter()
+        .rposition(|pending| matches!(pending.kont, Kont::Barrier(_)))
+        .expect("the stack of the evaluator has no barrier");
+}
+
+/**
+    Creates a continuation of the innermost evaluation: a copy of its stack entries.
+
+    Returns:    the continuation
+*/
+fn capture() -> Continuation {
+    return STACK.with(|stack| {
+        let stack = stack.borrow();
+        let base = barrier(&stack);
+        let run = match &stack[base].kont {
+            Kont::Barrier(run) => run.clone(),
+            _ => unreachable!(),
+        };
+        Continuation {
+            run,
+            frames: stack[base + 1..].to_vec(),
+        }
+    });
+}
+
+/**
+    Calls a continuation. If it belongs to the innermost evaluation, its frames replace the ones of the
+    evaluation. If it belongs to an outer evaluation, the inner evaluations are unwound first (see
+    RlErr::Escape). If its evaluation has finished already, the frames replace the ones of the innermost
+    evaluation, which then continues like the finished one would have.
+
+    Arguments:  continuation - the continuation
+                value - the value the call/cc of the continuation returns
+    Returns:    the step that returns the value to the frames of the continuation or an Error
+*/
+fn throw(continuation: &Rc<Continuation>, value: RlType) -> Result<Step, RlErr> {
+    let outer = STACK.with(|stack| {
+        let stack = stack.borrow();
+        stack[..barrier(&stack)].iter().any(|pending| match &pending.kont {
+            Kont::Barrier(run) => Rc::ptr_eq(run, &continuation.run),
+            _ => false,
+        })
+    });
+    if outer {
+        return Err(RlErr::Escape {
+            continuation: continuation.clone(),
+            value,
+        });
+    }
+    reinstate(continuation)?;
+    return Ok(Step::Return(value));
+}
+
+/**
+    Collects the thunks of the dynamic-winds among the given stack entries.
+
+    Arguments:  frames - the stack entries
+    Returns:    the thunks with the indices of their entries, outermost first
+*/
+fn winds(frames: &[Pending]) -> Vec<(usize, Rc<Wind>)> {
+    return frames
+        .iter()
+        .enumerate()
+        .filter_map(|(index, pending)| match &pending.kont {
+            Kont::Wind(wind) => Some((index, wind.clone())),
+            _ => None,
+        })
+        .collect();
+}
+
+/**
+    Replaces the stack entries of the innermost evaluation.
+
+    Arguments:  base - the index of the first entry above the barrier of the evaluation
+                frames - the new entries
+*/
+fn replace_frames(base: usize, frames: &[Pending]) {
+    STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        stack.truncate(base);
+        stack.extend_from_slice(frames);
+    });
+}
+
+/**
+    Finishes the coroutines whose functions are left when the stack entries of the innermost evaluation
+    are replaced: their ends are among the current entries but not among the new ones. They can't be
+    resumed anymore, as if their functions had failed.
+
+    Arguments:  current - the stack entries that are replaced
+                frames - the new entries
+*/
+fn finish_left_coroutines(current: &[Pending], frames: &[Pending]) {
+    let kept = |coroutine: &Rc<Coroutine>| {
+        frames.iter().any(|pending| match &pending.kont {
+            Kont::CoroutineEnd(other) => Rc::ptr_eq(coroutine, other),
+            _ => false,
+        })
+    };
+    for pending in current.iter() {
+        match &pending.kont {
+            Kont::CoroutineEnd(coroutine) if !kept(coroutine) => coroutine.finish(RlType::Nil),
+            _ => {}
+        }
+    }
+}
+
+/**
+    Replaces the stack entries of the innermost evaluation with the frames of a continuation. The after
+    thunks of the dynamic-winds that are left are called (innermost first), then the before thunks of
+    the dynamic-winds that are entered (outermost first).
+
+    Arguments:  continuation - the continuation
+    Returns:    nothing or the Error of a thunk
+*/
+fn reinstate(continuation: &Continuation) -> Result<(), RlErr> {
+    let base = STACK.with(|stack| barrier(&stack.borrow()) + 1);
+    STACK.with(|stack| finish_left_coroutines(&stack.borrow()[base..], &continuation.frames));
+    let current = STACK.with(|stack| winds(&stack.borrow()[base..]));
+    let target = winds(&continuation.frames);
+    let common = current
+        .iter()
+        .zip(target.iter())
+        .take_while(|((_, a), (_, b))| Rc::ptr_eq(a, b))
+        .count();
+    for (index, wind) in current[common..].iter().rev() {
+        // the thunk is left before the after thunk is called
+        STACK.with(|stack| stack.borrow_mut().truncate(base + index));
+        apply(vec![wind.after.clone()], &wind.env)?;
+    }
+    for (index, wind) in target[common..].iter() {
+        replace_frames(base, &continuation.frames[..*index]);
+        apply(vec![wind.before.clone()], &wind.env)?;
+    }
+    replace_frames(base, &continuation.frames);
+    return Ok(());
+}
+
+/**
+    Evaluates an expression as far as possible without evaluating a sub-expression.
+
+    Arguments:  expression - the expression to evaluate
+                environment - the environment the expression is evaluated in
+                location - is set to the location of the expression if it is a form that has one
+    Returns:    the next step or an Error
+*/
+fn eval_expression(
+    mut expression: RlType,
+    environment: RlEnv,
+    location: &mut Option<Rc<Location>>,
+) -> Result<Step, RlErr> {
+    return match &mut expression {
         // If given expression is a List
-        RlType::List(content) => {
+        RlType::List(content, form_location) => {
+            let (content, form_location) = (std::mem::take(content), form_location.take());
+            // remember the location of the form that is evaluated now (lists read from source code have one)
+            if let Some(form_location) = &form_location {
+                *location = Some(form_location.clone());
+            }
             // if list is empty return empty list back unchanged
-            return if content.len() == 0 {
-                Ok(expression)
+            if content.is_empty() {
+                return Ok(Step::Return(RlType::List(content, form_location)));
+            }
+            // if list is not empty first check first element if it is Symbol triggering special form
+            let special_form = match &content[0] {
+                RlType::Symbol(s) => special_form(s),
+                _ => None,
+            };
+            match special_form {
+                Some(handler) => handler(content, environment),
+                None => eval_call(content, environment),
+            }
+        }
+        // a list built of pairs is evaluated like the same list read from source code
+        RlType::Pair(..) => match list_to_vec(&expression) {
+            Some(_) => Ok(Step::Eval(to_code(&expression), environment)),
+            None => Err(type_error("a proper list to evaluate", &expression)),
+        },
+        // keywords (symbols starting with a colon, used for keyword arguments) evaluate to themselves
+        RlType::Symbol(s) if s.starts_with(':') => Ok(Step::Return(expression)),
+        // If given expression is no List but a Symbol, look up symbol in environment
+        RlType::Symbol(s) => Ok(Step::Return(search(&environment, std::mem::take(s))?)),
+        // Else given expression is self-evaluating
+        _ => Ok(Step::Return(expression)),
+    };
+}
+
+/**
+    Looks up the handler of a special form.
+
+    Arguments:  name - the symbol at the start of a form
+    Returns:    the handler or None if the name is no special form
+*/
+fn special_form(name: &str) -> Option<SpecialForm> {
+    let handler: SpecialForm = match name {
+        "quote" => eval_quote,
+        "quasiquote" => eval_quasiquote,
+        "unquote" | "unquote-splicing" => eval_unquote,
+        "eval" => eval_eval,
+        "cond" => eval_cond,
+        "if" => eval_if,
+        "when" | "unless" => eval_when,
+        "and" | "or" => eval_logic,
+        "case" => eval_case,
+        "define" => eval_define,
+        "set!" => eval_set,
+        "let" => eval_let,
+        "let*" => eval_let_star,
+        "letrec" | "letrec*" => eval_letrec,
+        "load" => eval_load,
+        "do" => eval_do,
+        "lambda" => eval_lambda,
+        "delay" => eval_delay,
+        "cons-stream" => eval_cons_stream,
+        "defmacro" => eval_defmacro,
+        "macroexpand-1" | "macroexpand" => eval_macroexpand,
+        "try" => eval_try,
+        _ => return None,
+    };
+    return Some(handler);
+}
+
+/**
+    Gets the name of a special form, for the forms that share a handler.
+
+    Arguments:  form - the elements of the form
+    Returns:    the symbol-name at the start of the form
+*/
+fn form_name(form: &[RlType]) -> &str {
+    return match &form[0] {
+        RlType::Symbol(s) => s,
+        _ => "",
+    };
+}
+
+/**
+    Passes the value of a sub-expression to the continuation frame that waits for it.
+
+    Arguments:  kont - the continuation frame
+                value - the value of the sub-expression
+    Returns:    the next step or an Error
+*/
+fn return_to(kont: Kont, value: RlType) -> Result<Step, RlErr> {
+    return match kont {
+        // the called function has returned (barriers are handled by run)
+        Kont::Barrier(_) | Kont::Call(_) => Ok(Step::Return(value)),
+        Kont::Args { call, mut values, env } => {
+            values.push(value);
+            next_argument(call, values, env)
+        }
+        Kont::Code(env) => Ok(Step::Eval(to_code(&value), env)),
+        Kont::Cond { form, index, env } => {
+            if truth(&value)? {
+                return Ok(Step::Eval(clause(&form[index])?[1].clone(), env));
+            }
+            next_clause(form, index + 1, env)
+        }
+        Kont::If { form, env } => {
+            if truth(&value)? {
+                Ok(Step::Eval(form[2].clone(), env))
+            } else if form.len() == 4 {
+                Ok(Step::Eval(form[3].clone(), env))
             } else {
-                // if list is not empty first check first element if it is Symbol triggering special form
-                let switcher = &content[0];
-                match switcher {
-                    // quote special form: takes exactly one argument and don't evaluates it
-                    RlType::Symbol(s) if s == "quote" => Ok(content[1].clone()),
-                    // eval special form: takes exactly one argument and evaluates is (needed for Homoiconicity)
-                    RlType::Symbol(s) if s == "eval" => {
-                        eval(content[1].clone(), environment.clone())
-                    }
-                    // cond special form: takes a list of pairs. Each pair has a predicate and an according
-                    //                    expression. Predicates are evaluated in order and the expression for
-                    //                    the first predicate to be true is evaluated.
-                    RlType::Symbol(s) if s == "cond" => {
-                        let pairs = content[1..].to_vec().clone();
-                        // iterate over pairs
-                        for pair in pairs.iter() {
-                            match pair {
-                                // if we have valid pair evaluate predicate
-                                RlType::List(l) if l.len() == 2 => {
-                                    match eval(l[0].clone(), environment.clone())? {
-                                        RlType::Bool(true) => {
-                                            return eval(l[1].clone(), environment.clone());
-                                        }
-                                        _ => {
-                                            continue;
-                                        }
-                                    }
-                                },
-                                // else pattern is invalid
-                                _ => return Err(error("Error: Wrong pattern for cond")),
-                            }
-                        }
-                        // if no predicate is evaluated to be true, return nil
-                        return Ok(RlType::Nil);
-                    }
-                    // define special form: takes a symbol-name and a target expression and maps the
-                    //                      symbol-name to the (evaluated)expression in current
-                    //                      environment. if symbol-name already defined, target is overwritten.
-                    RlType::Symbol(s) if s == "define" => {
-                        return if content[1..].len() != 2 {
-                            Err(error("Error: define takes exactly 2 ars"))
-                        } else {
-                            let key = match &content[1] {
-                                RlType::Symbol(s) => s.to_string(),
-                                _ => return Err(error("first arg of define must be a symbol")),
-                            };
-                            let target = eval(content[2].clone(), environment.clone())?;
-                            set(&environment, key.clone(), target.clone());
-                            Ok(target)
-                        }
-                    }
-                    // let special form: takes a list of pairs and an expression. The list of pairs
-                    //                   contains (symbol, value) pairs that will be defined in the
-                    //                   expression that was given as second environment.
-                    //                   Creates a new environment with the bindings and evaluates expression.
-                    RlType::Symbol(s) if s == "let" => {
-                        return if content[1..].len() != 2 {
-                            Err(error("Error: let takes exactly 2 args!"))
-                        } else {
-                            // check if first argument is a list
-                            let bindings_list = match &content[1] {
-                                RlType::List(l) => Ok(l),
-                                _ => Err(error("Error: Arguments of binding lists must be pairs!")),
-                            }?;
-                            // create new sub-environment with current environment as outer environment
-                            let new_env = new_env(Some(environment));
-                            // iterate over present binding pairs in the given List
-                            for binding in bindings_list.iter() {
-                                // check if element is a pair
-                                let b = match &binding {
-                                    RlType::List(l) if l.len() == 2 => Ok(l),
-                                    _ => Err(error(
-                                        "Error: bindings in let needs to be lists of len 2",
-                                    )),
-                                }?;
-                                // check if first element of pair is a symbol-name
-                                let key = match &b[0] {
-                                    RlType::Symbol(s) => s.to_string(),
-                                    _ => return Err(error("first arg of define must be a symbol")),
-                                };
-                                // map symbol to evaluated value in the new environment
-                                set(&new_env, key, eval(b[1].clone(), new_env.clone())?);
-                            }
-                            // Evaluate body with new environment
-                            eval(content[2].clone(), new_env.clone())
-                        };
-                    }
-                    // load special form: takes exactly one argument which is a string. This string
-                    //                    will be treated as filename. Try to load and evaluate content
-                    //                    of the file using the load() function in main.rs
-                    RlType::Symbol(s) if s == "load" => {
-                        // check if we have exactly one argument
-                        if content[1..].len() != 1 {
-                            return Err(error("load needs exactly one argument which is a string"));
-                        }
-                        // check if filename is a string
-                        let filename = match &content[1] {
-                            RlType::String(s) => s,
-                            _ => return Err(error("load a string as argument!")),
-                        };
-                        // use load() in main.rs to process file
-                        super::load(filename, environment.clone());
-                        // return nil since something needs to be returned
-                        Ok(RlType::Nil)
-                    }
-                    // do special form: takes a list of expressions, evaluates them in-order and
-                    //                  returns the value of the last expression evaluated.
-                    RlType::Symbol(s) if s == "do" => {
-                        // evaluate every expression except the last one
-                        for expression in content[1..content.len() - 1].iter() {
-                            let _ = eval(expression.clone(), environment.clone());
-                        }
-                        // evaluate last expression and return its value
-                        return eval(
-                            content.last().unwrap_or(&RlType::Nil).clone(),
-                            environment.clone(),
-                        );
+                Ok(Step::Return(RlType::Nil))
+            }
+        }
+        Kont::When { form, env } => {
+            if truth(&value)? != (form_name(&form) == "when") {
+                return Ok(Step::Return(RlType::Nil));
+            }
+            Ok(Step::Eval(body(&form[2..]), env))
+        }
+        Kont::Logic { form, index, env } => {
+            // and stops at the first false value, or at the first true value
+            if truth(&value)? == (form_name(&form) == "or") {
+                return Ok(Step::Return(value));
+            }
+            next_logic(form, index + 1, env)
+        }
+        Kont::Case { form, env } => case_branch(&form[2..], &value, env),
+        Kont::Define { key, env } => {
+            let target = match &value {
+                // anonymous functions are named after the symbol they are defined as
+                RlType::SelfDefinedFunc {
+                    env,
+                    params,
+                    body,
+                    name: None,
+                } => RlType::SelfDefinedFunc {
+                    env: env.clone(),
+                    params: params.clone(),
+                    body: body.clone(),
+                    name: Some(key.to_string()),
+                },
+                _ => value,
+            };
+            set(&env, key, target.clone());
+            Ok(Step::Return(target))
+        }
+        Kont::Assign { key, env } => {
+            assign(&env, key, value.clone())?;
+            Ok(Step::Return(value))
+        }
+        Kont::Let {
+            kind,
+            bindings,
+            mut values,
+            body,
+            env,
+        } => {
+            if let LetKind::Sequential = kind {
+                set(&env, bindings[values.len()].0.to_string(), value.clone());
+            }
+            values.push(value);
+            next_binding(kind, bindings, values, body, env)
+        }
+        Kont::Do { form, index, env } => next_do(form, index + 1, env),
+        Kont::ConsStream { tail, env } => {
+            let tail = Promise::Delayed(tail, env);
+            Ok(Step::Return(make_pair(value, RlType::Promise(Rc::new(RefCell::new(tail))))))
+        }
+        Kont::Macroexpand { once, env } => {
+            let form = to_code(&value);
+            if once {
+                return Ok(Step::Return(macroexpand_1(form, &env)?.0));
+            }
+            Ok(Step::Return(macroexpand(form, &env)?))
+        }
+        // the expression of try didn't fail
+        Kont::Try { .. } => Ok(Step::Return(value)),
+        Kont::Enter { wind, thunk } => {
+            let env = wind.env.clone();
+            Ok(Step::Call(Kont::Wind(wind), vec![thunk], env))
+        }
+        Kont::Wind(wind) => Ok(Step::Call(Kont::Value(value), vec![wind.after.clone()], wind.env.clone())),
+        Kont::Value(result) => Ok(Step::Return(result)),
+        Kont::Raise(err) => Err(err),
+        Kont::Each(mut each) => {
+            match each.primitive {
+                Primitive::Map => each.results.push(value),
+                Primitive::Filter | Primitive::Remove => {
+                    // filter keeps the elements the function is true for, remove the other ones
+                    if truth(&value)? == matches!(each.primitive, Primitive::Filter) {
+                        each.results.push(each.calls[each.index][0].clone());
                     }
-                    // lambda special form: takes two arguments, a list of formal arguments and an expression
-                    //                      lambda then creates a function in which the arguments are bound
-                    //                      to the according symbols in the given expression(=body of function)
-                    RlType::Symbol(s) if s == "lambda" => {
-                        // check if we have a list and an expression
-                        match (content[1].clone(), content[2].clone()) {
-                            (RlType::List(l1), body) => {
-                                // return function object. Stores environment at time of creation
-                                Ok(RlType::SelfDefinedFunc {
-                                    env: environment,
-                                    params: Rc::new(l1),
-                                    body: Rc::new(body)
-                                })
-                            },
-                            _ => Err(error("Error: lambda takes a list of parameters and an s-expression as body!"))
-                        }
+                }
+                Primitive::ForEach => {}
+                _ => each.acc = value,
+            }
+            each.index += 1;
+            next_each(each)
+        }
+        Kont::Force(promise) => {
+            // if it was forced during the evaluation, the first value is kept
+            if let Promise::Forced(first) = &*promise.borrow() {
+                return Ok(Step::Return(first.clone()));
+            }
+            promise.replace(Promise::Forced(value.clone()));
+            Ok(Step::Return(value))
+        }
+        // the function of the coroutine has returned, resume returns its result
+        Kont::CoroutineEnd(coroutine) => {
+            coroutine.finish(value.clone());
+            Ok(Step::Return(value))
+        }
+    };
+}
+
+/**
+    The quote special form: takes exactly one argument and doesn't evaluate it. The reader reads quoted
+    lists as pairs already, lists that come from macros or eval are converted.
+
+    Arguments:  form - the elements of the form
+                environment - the environment of the form
+    Returns:    the argument or an Error
+*/
+fn eval_quote(form: Vec<RlType>, _environment: RlEnv) -> Result<Step, RlErr> {
+    check_arity("quote", form[1..].len(), 1, Some(1))?;
+    return Ok(Step::Return(match &form[1] {
+        RlType::List(..) => to_data(&form[1]),
+        other => other.clone(),
+    }));
+}
+
+/**
+    The quasiquote special form: takes exactly one argument and doesn't evaluate it, except for
+    sub-expressions marked with unquote or unquote-splicing (see quasiquote).
+
+    Arguments:  form - the elements of the form
+                environment - the environment unquoted expressions are evaluated in
+    Returns:    the filled template or an Error
+*/
+fn eval_quasiquote(form: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    check_arity("quasiquote", form[1..].len(), 1, Some(1))?;
+    return Ok(Step::Return(quasiquote(&form[1], 1, &environment)?));
+}
+
+/**
+    unquote and unquote-splicing are only valid inside of a quasiquote.
+
+    Arguments:  form - the elements of the form
+                environment - the environment of the form
+    Returns:    a Syntax Error
+*/
+fn eval_unquote(form: Vec<RlType>, _environment: RlEnv) -> Result<Step, RlErr> {
+    return Err(RlErr::Syntax(format!("{} is only allowed inside of quasiquote", form_name(&form))));
+}
+
+/**
+    The eval special form: takes exactly one argument and evaluates it (needed for Homoiconicity).
+    The result is evaluated again in tail position.
+
+    Arguments:  form - the elements of the form
+                environment - the environment of the form
+    Returns:    the next step or an Error
+*/
+fn eval_eval(form: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    check_arity("eval", form[1..].len(), 1, Some(1))?;
+    return Ok(Step::Sub(Kont::Code(environment.clone()), form[1].clone(), environment));
+}
+
+/**
+    The cond special form: takes a list of pairs. Each pair has a predicate and an according
+    expression. Predicates are evaluated in order and the expression for the first predicate to be
+    true is evaluated (in tail position). If no predicate is true, nil is returned.
+
+    Arguments:  form - the elements of the form
+                environment - the environment of the form
+    Returns:    the next step or an Error
+*/
+fn eval_cond(form: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    return next_clause(form, 1, environment);
+}
+
+/**
+    Evaluates the predicate of the clause at the given index of a cond form.
+
+    Arguments:  form - the elements of the cond form
+                index - the index of the clause
+                environment - the environment of the form
+    Returns:    the next step, nil if there are no clauses left or an Error for an invalid clause
+*/
+fn next_clause(form: Vec<RlType>, index: usize, environment: RlEnv) -> Result<Step, RlErr> {
+    if index == form.len() {
+        return Ok(Step::Return(RlType::Nil));
+    }
+    let predicate = clause(&form[index])?[0].clone();
+    return Ok(Step::Sub(
+        Kont::Cond {
+            form,
+            index,
+            env: environment.clone(),
+        },
+        predicate,
+        environment,
+    ));
+}
+
+/**
+    Checks that a clause of cond is a (predicate expression) pair.
+
+    Arguments:  clause - the clause
+    Returns:    the predicate and the expression or an Error
+*/
+fn clause(clause: &RlType) -> Result<&[RlType], RlErr> {
+    return match clause {
+        RlType::List(l, _) if l.len() == 2 => Ok(l),
+        other => Err(type_error("a (predicate expression) pair in cond", other)),
+    };
+}
+
+/**
+    The if special form: takes a test, a consequent and an optional alternative. If the test is true
+    the consequent is evaluated, otherwise the alternative (in tail position). Without an alternative
+    a false test returns nil.
+
+    Arguments:  form - the elements of the form
+                environment - the environment of the form
+    Returns:    the next step or an Error
+*/
+fn eval_if(form: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    check_arity("if", form[1..].len(), 2, Some(3))?;
+    let test = form[1].clone();
+    return Ok(Step::Sub(
+        Kont::If {
+            form,
+            env: environment.clone(),
+        },
+        test,
+        environment,
+    ));
+}
+
+/**
+    The when and unless special forms: take a test and a body. The body is evaluated (in tail
+    position) if the test is true (when) or false (unless), otherwise nil is returned.
+
+    Arguments:  form - the elements of the form
+                environment - the environment of the form
+    Returns:    the next step or an Error
+*/
+fn eval_when(form: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    check_arity(form_name(&form), form[1..].len(), 2, None)?;
+    let test = form[1].clone();
+    return Ok(Step::Sub(
+        Kont::When {
+            form,
+            env: environment.clone(),
+        },
+        test,
+        environment,
+    ));
+}
+
+/**
+    The and and or special forms: evaluate their arguments in order. and stops at the first false
+    value, or at the first true value, which is returned. Otherwise the last argument is evaluated in
+    tail position. (and) is #t, (or) is #f.
+
+    Arguments:  form - the elements of the form
+                environment - the environment of the form
+    Returns:    the next step or an Error
+*/
+fn eval_logic(form: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    if form.len() == 1 {
+        return Ok(Step::Return(RlType::Bool(form_name(&form) == "and")));
+    }
+    return next_logic(form, 1, environment);
+}
+
+/**
+    Evaluates the argument at the given index of an and or or form, the last one in tail position.
+
+    Arguments:  form - the elements of the form
+                index - the index of the argument
+                environment - the environment of the form
+    Returns:    the next step
+*/
+fn next_logic(form: Vec<RlType>, index: usize, environment: RlEnv) -> Result<Step, RlErr> {
+    let argument = form[index].clone();
+    if index == form.len() - 1 {
+        return Ok(Step::Eval(argument, environment));
+    }
+    return Ok(Step::Sub(
+        Kont::Logic {
+            form,
+            index,
+            env: environment.clone(),
+        },
+        argument,
+        environment,
+    ));
+}
+
+/**
+    The case special form: takes a key and clauses ((datum ...) body) or (else body). The key is
+    evaluated and the body of the first clause with a datum equal (like eq?) to it is evaluated in
+    tail position. The data are not evaluated. If no clause matches, nil is returned.
+
+    Arguments:  form - the elements of the form
+                environment - the environment of the form
+    Returns:    the next step or an Error
+*/
+fn eval_case(form: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    check_arity("case", form[1..].len(), 1, None)?;
+    let key = form[1].clone();
+    return Ok(Step::Sub(
+        Kont::Case {
+            form,
+            env: environment.clone(),
+        },
+        key,
+        environment,
+    ));
+}
+
+/**
+    Chooses the clause of a case form that matches the key.
+
+    Arguments:  clauses - the clauses of the case form
+                key - the value of the key
+                environment - the environment of the form
+    Returns:    the step that evaluates the body of the matching clause, nil if no clause matches or
+                an Error for an invalid clause
+*/
+fn case_branch(clauses: &[RlType], key: &RlType, environment: RlEnv) -> Result<Step, RlErr> {
+    for clause in clauses.iter() {
+        let (data, clause_body) = match clause {
+            RlType::List(l, _) if l.len() >= 2 => (&l[0], &l[1..]),
+            other => return Err(type_error("a ((datum ...) expression) clause in case", other)),
+        };
+        let matches = match data {
+            RlType::Symbol(d) if d == "else" => true,
+            RlType::List(d, _) => d.contains(key),
+            other => return Err(type_error("a list of data or else in case", other)),
+        };
+        if matches {
+            return Ok(Step::Eval(body(clause_body), environment));
+        }
+    }
+    return Ok(Step::Return(RlType::Nil));
+}
+
+/**
+    The define special form: takes a symbol-name and a target expression and maps the symbol-name to
+    the (evaluated) expression in the current environment. If the symbol-name is already defined, the
+    target is overwritten.
+
+    Arguments:  form - the elements of the form
+                environment - the environment of the form
+    Returns:    the next step or an Error
+*/
+fn eval_define(form: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    check_arity("define", form[1..].len(), 2, Some(2))?;
+    let key = match &form[1] {
+        RlType::Symbol(s) => s.to_string(),
+        other => return Err(type_error("a symbol as first argument of define", other)),
+    };
+    return Ok(Step::Sub(
+        Kont::Define {
+            key,
+            env: environment.clone(),
+        },
+        form[2].clone(),
+        environment,
+    ));
+}
+
+/**
+    The set! special form: takes a symbol-name and an expression and changes the value of the symbol
+    to the (evaluated) expression in the innermost environment the symbol is defined in. The symbol
+    must be defined already.
+
+    Arguments:  form - the elements of the form
+                environment - the environment of the form
+    Returns:    the next step or an Error
+*/
+fn eval_set(form: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    check_arity("set!", form[1..].len(), 2, Some(2))?;
+    let key = match &form[1] {
+        RlType::Symbol(s) => s.to_string(),
+        other => return Err(type_error("a symbol as first argument of set!", other)),
+    };
+    return Ok(Step::Sub(
+        Kont::Assign {
+            key,
+            env: environment.clone(),
+        },
+        form[2].clone(),
+        environment,
+    ));
+}
+
+/**
+    The let special form: takes a list of pairs and a body. The list of pairs contains (symbol, value)
+    pairs, all values are evaluated in the current environment first. Then a new environment with the
+    bindings is created and the body is evaluated in it (tail position).
+    Named let (let name bindings body) binds name to a function with the symbols as parameters and the
+    body, calling it repeats the body.
+
+    Arguments:  form - the elements of the form
+                environment - the environment of the form
+    Returns:    the next step or an Error
+*/
+fn eval_let(form: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    check_arity("let", form[1..].len(), 2, None)?;
+    if let RlType::Symbol(name) = &form[1] {
+        check_arity("let", form[1..].len(), 3, None)?;
+        let bindings = let_bindings(&form[2])?;
+        let params: Vec<RlType> = bindings.iter().map(|(key, _)| RlType::Symbol(key.to_string())).collect();
+        let function_body = body(&form[3..]);
+        // the function can call itself, so it is defined in its own environment
+        let loop_env = new_env(Some(environment.clone()));
+        let params = Rc::new(params);
+        let function = RlType::SelfDefinedFunc {
+            env: loop_env.clone(),
+            params: params.clone(),
+            body: Rc::new(function_body.clone()),
+            name: Some(name.to_string()),
+        };
+        set(&loop_env, name.to_string(), function);
+        // the first call gets the values of the bindings
+        let kind = LetKind::Named {
+            name: name.to_string(),
+            env: loop_env,
+            params,
+        };
+        return next_binding(kind, bindings, Vec::new(), function_body, environment);
+    }
+    let bindings = let_bindings(&form[1])?;
+    return next_binding(LetKind::Parallel, bindings, Vec::new(), body(&form[2..]), environment);
+}
+
+/**
+    The let* special form: like let, but the values are evaluated in order in the new environment, so
+    each value can use the symbols bound before it.
+
+    Arguments:  form - the elements of the form
+                environment - the environment of the form
+    Returns:    the next step or an Error
+*/
+fn eval_let_star(form: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    check_arity("let*", form[1..].len(), 2, None)?;
+    let bindings = let_bindings(&form[1])?;
+    let new_env = new_env(Some(environment));
+    return next_binding(LetKind::Sequential, bindings, Vec::new(), body(&form[2..]), new_env);
+}
+
+/**
+    The letrec and letrec* special forms: like let, but all symbols are bound (to #nil) in the new
+    environment before the values are evaluated in it, so the values can be mutually recursive
+    functions. letrec evaluates all values before it binds them, letrec* binds each value right after
+    it is evaluated.
+
+    Arguments:  form - the elements of the form
+                environment - the environment of the form
+    Returns:    the next step or an Error
+*/
+fn eval_letrec(form: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    check_arity(form_name(&form), form[1..].len(), 2, None)?;
+    let bindings = let_bindings(&form[1])?;
+    let new_env = new_env(Some(environment));
+    for (key, _) in bindings.iter() {
+        set(&new_env, key.to_string(), RlType::Nil);
+    }
+    let kind = match form_name(&form) {
+        "letrec*" => LetKind::Sequential,
+        _ => LetKind::Recursive,
+    };
+    return next_binding(kind, bindings, Vec::new(), body(&form[2..]), new_env);
+}
+
+/**
+    Evaluates the value of the next binding of a let form. When all values are evaluated, the symbols
+    are bound (depending on the kind of let) and the body is evaluated in tail position.
+
+    Arguments:  kind - the kind of let
+                bindings - the symbol-names with the value expressions
+                values - the values that are evaluated already
+                body - the body of the let form
+                environment - the environment the values are evaluated in
+    Returns:    the next step or an Error
+*/
+fn next_binding(
+    kind: LetKind,
+    bindings: Vec<(String, RlType)>,
+    values: Vec<RlType>,
+    body: RlType,
+    environment: RlEnv,
+) -> Result<Step, RlErr> {
+    if values.len() < bindings.len() {
+        let value = bindings[values.len()].1.clone();
+        let kont = Kont::Let {
+            kind,
+            bindings,
+            values,
+            body,
+            env: environment.clone(),
+        };
+        return Ok(Step::Sub(kont, value, environment));
+    }
+    return match kind {
+        // create new sub-environment with current environment as outer environment
+        LetKind::Parallel => {
+            let new_env = new_env(Some(environment));
+            for ((key, _), value) in bindings.into_iter().zip(values) {
+                set(&new_env, key, value);
+            }
+            Ok(Step::Eval(body, new_env))
+        }
+        LetKind::Named { name, env, params } => Ok(Step::Eval(body, new_env_bound(Some(env), &name, &params, values)?)),
+        LetKind::Sequential => Ok(Step::Eval(body, environment)),
+        LetKind::Recursive => {
+            for ((key, _), value) in bindings.into_iter().zip(values) {
+                set(&environment, key, value);
+            }
+            Ok(Step::Eval(body, environment))
+        }
+    };
+}
+
+/**
+    The load special form: takes exactly one argument which is a string. This string will be treated
+    as filename. Try to load and evaluate content of the file using the load() function in lib.rs
+
+    Arguments:  form - the elements of the form
+                environment - the environment the file is evaluated in
+    Returns:    the value of the last expression of the file or an Error
+*/
+fn eval_load(form: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    // check if we have exactly one argument
+    check_arity("load", form[1..].len(), 1, Some(1))?;
+    // check if filename is a string
+    let filename = match &form[1] {
+        RlType::String(s) => s,
+        other => return Err(type_error("a string as filename", other)),
+    };
+    // use load() in lib.rs to process file, it returns the value of the last expression
+    return Ok(Step::Return(crate::load(filename, environment)?));
+}
+
+/**
+    The do special form: takes a list of expressions, evaluates them in-order and returns the value of
+    the last expression evaluated (in tail position). An error stops the do.
+
+    Arguments:  form - the elements of the form
+                environment - the environment of the form
+    Returns:    the next step
+*/
+fn eval_do(form: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    if form.len() == 1 {
+        return Ok(Step::Return(RlType::Nil));
+    }
+    return next_do(form, 1, environment);
+}
+
+/**
+    Evaluates the expression at the given index of a do form, the last one in tail position.
+
+    Arguments:  form - the elements of the form
+                index - the index of the expression
+                environment - the environment of the form
+    Returns:    the next step
+*/
+fn next_do(form: Vec<RlType>, index: usize, environment: RlEnv) -> Result<Step, RlErr> {
+    let expression = form[index].clone();
+    if index == form.len() - 1 {
+        return Ok(Step::Eval(expression, environment));
+    }
+    return Ok(Step::Sub(
+        Kont::Do {
+            form,
+            index,
+            env: environment.clone(),
+        },
+        expression,
+        environment,
+    ));
+}
+
+/**
+    The lambda special form: takes a list of formal arguments and a body (one or more expressions).
+    lambda then creates a function in which the arguments are bound to the according symbols in the
+    given expression(=body of function).
+
+    Arguments:  form - the elements of the form
+                environment - the environment the function is created in
+    Returns:    the function or an Error
+*/
+fn eval_lambda(form: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    check_arity("lambda", form[1..].len(), 2, None)?;
+    // the parameters are a list (a b), a dotted list (a . rest) or a symbol (all arguments)
+    let params = parameter_list(&form[1])?;
+    // return function object. Stores environment at time of creation
+    return Ok(Step::Return(RlType::SelfDefinedFunc {
+        env: environment,
+        params: Rc::new(params),
+        body: Rc::new(body(&form[2..])),
+        name: None,
+    }));
+}
+
+/**
+    The delay special form: takes one expression and returns a promise holding it and the current
+    environment, the expression is evaluated when the promise is forced.
+
+    Arguments:  form - the elements of the form
+                environment - the environment of the form
+    Returns:    the promise or an Error
+*/
+fn eval_delay(form: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    check_arity("delay", form[1..].len(), 1, Some(1))?;
+    return Ok(Step::Return(RlType::Promise(Rc::new(RefCell::new(Promise::Delayed(
+        form[1].clone(),
+        environment,
+    ))))));
+}
+
+/**
+    The cons-stream special form: takes two expressions and returns a pair of the value of the first
+    one and a promise of the second one: (cons a (delay b))
+
+    Arguments:  form - the elements of the form
+                environment - the environment of the form
+    Returns:    the next step or an Error
+*/
+fn eval_cons_stream(form: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    check_arity("cons-stream", form[1..].len(), 2, Some(2))?;
+    let kont = Kont::ConsStream {
+        tail: form[2].clone(),
+        env: environment.clone(),
+    };
+    return Ok(Step::Sub(kont, form[1].clone(), environment));
+}
+
+/**
+    The defmacro special form: takes a symbol-name, a list of formal arguments and an expression.
+    Creates a macro and maps the symbol-name to it in the current environment. When a macro is
+    called, its arguments are bound unevaluated and the result of the body is evaluated instead of
+    the call.
+
+    Arguments:  form - the elements of the form
+                environment - the environment the macro is defined in
+    Returns:    the macro or an Error
+*/
+fn eval_defmacro(form: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    check_arity("defmacro", form[1..].len(), 3, Some(3))?;
+    let key = match &form[1] {
+        RlType::Symbol(s) => s.to_string(),
+        other => return Err(type_error("a symbol as first argument of defmacro", other)),
+    };
+    let params = parameter_list(&form[2])?;
+    // like functions, macros store the environment at time of creation
+    let new_macro = RlType::Macro {
+        env: environment.clone(),
+        params: Rc::new(params),
+        body: Rc::new(form[3].clone()),
+    };
+    set(&environment, key, new_macro.clone());
+    return Ok(Step::Return(new_macro));
+}
+
+/**
+    The macroexpand-1 special form: takes one argument, evaluates it and expands the result once if it
+    is a macro call. The expansion is not evaluated. macroexpand expands the result repeatedly until it
+    is no macro call anymore.
+
+    Arguments:  form - the elements of the form
+                environment - the environment of the form
+    Returns:    the next step or an Error
+*/
+fn eval_macroexpand(form: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    let once = form_name(&form) == "macroexpand-1";
+    check_arity(form_name(&form), form[1..].len(), 1, Some(1))?;
+    let kont = Kont::Macroexpand {
+        once,
+        env: environment.clone(),
+    };
+    return Ok(Step::Sub(kont, form[1].clone(), environment));
+}
+
+/**
+    The try special form: takes an expression and a catch clause (catch symbol handler). If the
+    evaluation of the expression fails, the raised value (or an error object for internal errors) is
+    bound to the symbol and the handler is evaluated instead (in tail position, see unwind).
+
+    Arguments:  form - the elements of the form
+                environment - the environment of the form
+    Returns:    the next step or an Error
+*/
+fn eval_try(form: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    check_arity("try", form[1..].len(), 2, Some(2))?;
+    // check if catch clause is valid
+    let (name, handler) = match &form[2] {
+        RlType::List(l, _) if l.len() == 3 && l[0] == RlType::Symbol("catch".to_string()) => match &l[1] {
+            RlType::Symbol(name) => (name.to_string(), l[2].clone()),
+            other => return Err(type_error("a symbol to bind the error to", other)),
+        },
+        other => return Err(type_error("a (catch symbol handler) clause", other)),
+    };
+    let kont = Kont::Try {
+        name,
+        handler,
+        env: environment.clone(),
+    };
+    return Ok(Step::Sub(kont, form[1].clone(), environment));
+}
+
+/**
+    Evaluates a list that is no special form. If it is a macro call, the expansion is evaluated
+    instead (tail position). Otherwise every element of the list is evaluated and the value of the
+    first one is applied to the others.
+
+    Arguments:  call - the elements of the list
+                environment - the environment of the list
+    Returns:    the next step or an Error
+*/
+fn eval_call(call: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    let mut values = Vec::with_capacity(call.len());
+    if let RlType::Symbol(name) = &call[0] {
+        if !name.starts_with(':') {
+            match search(&environment, name.to_string())? {
+                RlType::Macro { ref env, ref params, ref body } => {
+                    let expansion = expand(name, env.clone(), params, body, &call[1..])?;
+                    return Ok(Step::Eval(expansion, environment));
+                }
+                function => values.push(function),
+            }
+        }
+    }
+    return next_argument(call, values, environment);
+}
+
+/**
+    Evaluates the elements of a function call that are not evaluated yet. Atoms are evaluated right
+    away, for lists a continuation frame is pushed. When all elements are evaluated, the function is
+    applied.
+
+    Arguments:  call - the elements of the call
+                values - the values of the elements that are evaluated already
+                environment - the environment of the call
+    Returns:    the next step or an Error
+*/
+fn next_argument(call: Vec<RlType>, mut values: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    while values.len() < call.len() {
+        match &call[values.len()] {
+            RlType::List(l, _) if l.is_empty() => values.push(call[values.len()].clone()),
+            RlType::List(..) | RlType::Pair(..) => {
+                let argument = call[values.len()].clone();
+                let kont = Kont::Args {
+                    call,
+                    values,
+                    env: environment.clone(),
+                };
+                return Ok(Step::Sub(kont, argument, environment));
+            }
+            RlType::Symbol(s) if !s.starts_with(':') => values.push(search(&environment, s.to_string())?),
+            other => values.push(other.clone()),
+        }
+    }
+    return Ok(Step::Apply(values, environment));
+}
+
+/**
+    Applies a function to arguments. Calls of self defined functions are in tail position: the
+    parameters are bound and the body is evaluated next, the call is recorded for the backtrace.
+
+    Arguments:  args - the function and the arguments
+                environment - the environment of the caller, it is passed on to builtins
+                location - the location of the call, if known
+    Returns:    the next step or an Error
+*/
+fn apply_function(args: Vec<RlType>, environment: RlEnv, location: &Option<Rc<Location>>) -> Result<Step, RlErr> {
+    return match &args[0] {
+        RlType::SelfDefinedFunc {
+            env: stored_env,
+            params,
+            body,
+            name,
+        } => {
+            let args = args[1..].to_vec();
+            // bind the arguments, errors are reported after the call is recorded
+            let bound = new_env_bound(
+                Some(stored_env.clone()),
+                name.as_deref().unwrap_or("function"),
+                params,
+                args.clone(),
+            );
+            push_call(Frame {
+                name: name.clone(),
+                args,
+                location: location.clone(),
+            });
+            Ok(Step::Eval((**body).clone(), bound?))
+        }
+        RlType::Func(_) | RlType::Builtin(_) => Ok(Step::Return(apply(args, &environment)?)),
+        RlType::Primitive(primitive) => {
+            let (min_args, max_args) = primitive.arity();
+            check_arity(primitive.name(), args.len() - 1, min_args, max_args)?;
+            apply_primitive(primitive.clone(), args, environment)
+        }
+        // calling a continuation continues its evaluation with the given value (#nil if there is none)
+        RlType::Continuation(continuation) => {
+            check_arity("continuation", args.len() - 1, 0, Some(1))?;
+            throw(continuation, args.get(1).cloned().unwrap_or(RlType::Nil))
+        }
+        _ => Err(type_error("a function to apply", &args[0])),
+    };
+}
+
+/**
+    Applies a primitive (a builtin function that calls other functions) to arguments. The calls of the
+    other functions are steps of the evaluation, so continuations can leave and enter them.
+    (call/cc f) calls f with the current continuation (in tail position).
+    (dynamic-wind before thunk after) calls the three functions without arguments in order and returns
+    the result of thunk. after is called whenever thunk is left (by an error or a continuation), before
+    whenever a continuation enters thunk again.
+    (apply f arg1 ... list) calls f with the arguments followed by the elements of the list.
+    (force promise) evaluates the expression of the promise the first time and returns its value, later
+    calls return the same value. Other values than promises are returned unchanged.
+    map, for-each, filter, remove, reduce, fold-left and fold-right call their function for each element
+    of the lists (see next_each).
+    (resume coroutine args ...) continues the coroutine until it yields or returns, (yield value)
+    suspends the coroutine and a generator resumes its coroutine (see resume and suspend).
+
+    Arguments:  primitive - the primitive
+                args - the primitive and its arguments, the number of arguments is checked already
+                environment - the environment of the caller
+    Returns:    the next step or an Error
+*/
+fn apply_primitive(primitive: Primitive, args: Vec<RlType>, environment: RlEnv) -> Result<Step, RlErr> {
+    let a = &args[1..];
+    let (calls, acc) = match &primitive {
+        Primitive::CallCc => {
+            let continuation = RlType::Continuation(Rc::new(capture()));
+            return Ok(Step::Apply(vec![a[0].clone(), continuation], environment));
+        }
+        Primitive::DynamicWind => {
+            let wind = Rc::new(Wind {
+                before: a[0].clone(),
+                after: a[2].clone(),
+                env: environment.clone(),
+            });
+            let kont = Kont::Enter {
+                wind,
+                thunk: a[1].clone(),
+            };
+            return Ok(Step::Call(kont, vec![a[0].clone()], environment));
+        }
+        Primitive::Apply => {
+            let mut call = a[..a.len() - 1].to_vec();
+            call.extend(list_elements(&a[a.len() - 1])?);
+            return Ok(Step::Apply(call, environment));
+        }
+        Primitive::Resume => {
+            return match &a[0] {
+                RlType::Coroutine(coroutine) => resume(coroutine, a[1..].to_vec()),
+                other => Err(type_error("a coroutine", other)),
+            };
+        }
+        Primitive::Yield(coroutine) => {
+            return suspend(coroutine.as_ref(), a.first().cloned().unwrap_or(RlType::Nil));
+        }
+        Primitive::Generator(coroutine) => {
+            // when the function has returned, the generator returns its result
+            if let Some(result) = coroutine.result() {
+                return Ok(Step::Return(result));
+            }
+            // the function gets a yield that is bound to the coroutine of the generator
+            let args = match coroutine.started() {
+                true => vec![],
+                false => vec![RlType::Primitive(Primitive::Yield(Some(coroutine.clone())))],
+            };
+            return resume(coroutine, args);
+        }
+        Primitive::Force => {
+            return match &a[0] {
+                RlType::Promise(promise) => match &*promise.borrow() {
+                    Promise::Forced(value) => Ok(Step::Return(value.clone())),
+                    // the promise is not borrowed while it is evaluated, so the expression can force it as well
+                    Promise::Delayed(expression, env) => {
+                        Ok(Step::Sub(Kont::Force(promise.clone()), expression.clone(), env.clone()))
                     }
-                    _ => {
-                        // Else evaluate every subexpression of the list and apply
-                        let mut evaluated = Vec::new();
-                        for element in content.iter() {
-                            evaluated.push(eval(element.clone(), environment.clone())?);
-                        }
-                        apply(evaluated)
+                },
+                other => Ok(Step::Return(other.clone())),
+            };
+        }
+        // (map f list1 list2 ...) returns the list of the results of f applied to the elements of the lists,
+        // (for-each f list1 list2 ...) calls f just for its side effects and returns #nil
+        Primitive::Map | Primitive::ForEach => (zip_lists(&a[1..])?, RlType::Nil),
+        // (filter pred list) returns the elements the predicate is true for, remove the other ones
+        Primitive::Filter | Primitive::Remove => {
+            let calls = list_elements(&a[1])?.into_iter().map(|element| vec![element]).collect();
+            (calls, RlType::Nil)
+        }
+        // (reduce f initial list) combines the elements from left to right with (f element accumulator),
+        // starting with the first element. Returns initial for an empty list
+        Primitive::Reduce => {
+            let mut elements = list_elements(&a[2])?.into_iter();
+            let Some(first) = elements.next() else {
+                return Ok(Step::Return(a[1].clone()));
+            };
+            (elements.map(|element| vec![element]).collect(), first)
+        }
+        // (fold-left f initial list1 list2 ...) combines from left to right with (f accumulator elements ...)
+        Primitive::FoldLeft => (zip_lists(&a[2..])?, a[1].clone()),
+        // (fold-right f initial list1 list2 ...) combines from right to left with (f elements ... accumulator)
+        Primitive::FoldRight => {
+            let mut calls = zip_lists(&a[2..])?;
+            calls.reverse();
+            (calls, a[1].clone())
+        }
+    };
+    return next_each(Each {
+        primitive,
+        func: a[0].clone(),
+        calls: Rc::new(calls),
+        index: 0,
+        results: Vec::new(),
+        acc,
+        env: environment,
+    });
+}
+
+/**
+    Continues a coroutine: the function is called the first time, later the stack entries of the
+    coroutine are put back on the stack and the value is returned to the yield that suspended it.
+
+    Arguments:  coroutine - the coroutine
+                args - the arguments of the function, or the value for yield (the first one)
+    Returns:    the next step or an Error if the coroutine is running already or has finished
+*/
+fn resume(coroutine: &Rc<Coroutine>, args: Vec<RlType>) -> Result<Step, RlErr> {
+    return match coroutine.resume(args)? {
+        Resumed::Start(call, environment) => Ok(Step::Call(Kont::CoroutineEnd(coroutine.clone()), call, environment)),
+        Resumed::Continue(frames, value) => {
+            STACK.with(|stack| {
+                let mut stack = stack.borrow_mut();
+                stack.push(Pending {
+                    kont: Kont::CoroutineEnd(coroutine.clone()),
+                    location: None,
+                });
+                stack.extend(frames);
+            });
+            Ok(Step::Return(value))
+        }
+    };
+}
+
+/**
+    Suspends a coroutine: its stack entries are moved into the coroutine and the value is returned to
+    the resume that continued it. Coroutines that run inside of the coroutine are suspended with it.
+
+    Arguments:  coroutine - the coroutine to suspend, None for the innermost one
+                value - the value resume returns
+    Returns:    the next step or an Error if the coroutine is not running in the current evaluation
+*/
+fn suspend(coroutine: Option<&Rc<Coroutine>>, value: RlType) -> Result<Step, RlErr> {
+    let suspended = STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        let index = stack.iter().rposition(|pending| match &pending.kont {
+            Kont::CoroutineEnd(running) => coroutine.is_none_or(|target| Rc::ptr_eq(target, running)),
+            _ => false,
+        });
+        let Some(index) = index else {
+            return Err(RlErr::State(match coroutine {
+                None => "yield can just be called inside of a coroutine".to_string(),
+                Some(_) => "yield can just be called while its coroutine is running".to_string(),
+            }));
+        };
+        // the Rust code of a builtin between the coroutine and the yield can't be suspended
+        if index < barrier(&stack) {
+            return Err(RlErr::State("yield can't suspend a coroutine from inside of a builtin".to_string()));
+        }
+        let frames = stack.split_off(index + 1);
+        return match stack.pop().map(|pending| pending.kont) {
+            Some(Kont::CoroutineEnd(running)) => Ok((running, frames)),
+            _ => unreachable!(),
+        };
+    });
+    let (running, frames) = suspended?;
+    running.suspend(frames);
+    return Ok(Step::Return(value));
+}
+
+/**
+    Makes the next call of map, for-each, filter, remove, reduce, fold-left or fold-right. When all calls
+    are made, the result is returned.
+
+    Arguments:  each - the state of the primitive
+    Returns:    the next step
+*/
+fn next_each(each: Each) -> Result<Step, RlErr> {
+    if each.index == each.calls.len() {
+        return Ok(Step::Return(match each.primitive {
+            Primitive::Map | Primitive::Filter | Primitive::Remove => list_from_vec(each.results),
+            Primitive::ForEach => RlType::Nil,
+            _ => each.acc,
+        }));
+    }
+    let mut call = vec![each.func.clone()];
+    match each.primitive {
+        Primitive::FoldLeft => {
+            call.push(each.acc.clone());
+            call.extend(each.calls[each.index].iter().cloned());
+        }
+        Primitive::Reduce | Primitive::FoldRight => {
+            call.extend(each.calls[each.index].iter().cloned());
+            call.push(each.acc.clone());
+        }
+        _ => call.extend(each.calls[each.index].iter().cloned()),
+    }
+    let environment = each.env.clone();
+    return Ok(Step::Call(Kont::Each(each), call, environment));
+}
+
+/**
+    Runs the given function with the strict-boolean option set as given (see truth), afterwards the
+    option is set back. The Interpreter sets its option like this for every evaluation.
+
+    Arguments:  strict - true if tests of conditionals must be booleans
+                f - the function to run
+    Returns:    the result of the function
+*/
+pub(crate) fn with_strict_booleans<T>(strict: bool, f: impl FnOnce() -> T) -> T {
+    let outer = STRICT_BOOLEANS.with(|option| option.replace(strict));
+    let result = f();
+    STRICT_BOOLEANS.with(|option| option.set(outer));
+    return result;
+}
+
+/**
+    Decides if the value of a test (of cond, if, when, unless, and, or or a predicate function) is true.
+    Every value except #f and #nil is true. With the strict-boolean option of the interpreter, the
+    value must be a boolean instead.
+
+    Arguments:  value - the value of the test
+    Returns:    the truth value or a type Error if the option is set and the value is no boolean
+*/
+pub(crate) fn truth(value: &RlType) -> Result<bool, RlErr> {
+    if let RlType::Bool(b) = value {
+        return Ok(*b);
+    }
+    if STRICT_BOOLEANS.with(Cell::get) {
+        return Err(type_error("a boolean as test", value));
+    }
+    return Ok(is_truthy(value));
+}
+
+/**
+    Parses the bindings of let, let*, letrec and letrec*: a list of (symbol value) pairs.
+
+    Arguments:  bindings - the list of bindings
+    Returns:    the symbol-names with the (unevaluated) value expressions or an Error
+*/
+fn let_bindings(bindings: &RlType) -> Result<Vec<(String, RlType)>, RlErr> {
+    // check if first argument is a list
+    let bindings_list = match bindings {
+        RlType::List(l, _) => l,
+        other => return Err(type_error("a list of bindings", other)),
+    };
+    let mut result = Vec::new();
+    // iterate over present binding pairs in the given List
+    for binding in bindings_list.iter() {
+        // check if element is a pair
+        let b = match &binding {
+            RlType::List(l, _) if l.len() == 2 => l,
+            other => return Err(type_error("a (symbol value) pair as binding", other)),
+        };
+        // check if first element of pair is a symbol-name
+        match &b[0] {
+            RlType::Symbol(s) => result.push((s.to_string(), b[1].clone())),
+            other => return Err(type_error("a symbol as binding name", other)),
+        }
+    }
+    return Ok(result);
+}
+
+/**
+    Builds the body of a function or a let form. Several expressions are evaluated in order like with
+    do, so the body can start with internal definitions: (define ...) expressions that bind symbols in
+    the environment of the function call (or the let form) only.
+
+    Arguments:  expressions - the expressions of the body
+    Returns:    the body as one expression
+*/
+fn body(expressions: &[RlType]) -> RlType {
+    if expressions.len() == 1 {
+        return expressions[0].clone();
+    }
+    let mut body = vec![RlType::Symbol("do".to_string())];
+    body.extend(expressions.iter().cloned());
+    return RlType::List(body, None);
+}
+
+/**
+    Builds the result of a quasiquote special form. The given template is returned unevaluated, except
+    for the sub-expressions (unquote x), whose value replaces them, and (unquote-splicing x), whose
+    value must be a list and whose elements are inserted in place of them. Dotted templates are filled
+    as well. Inside of a nested quasiquote the unquotes belong to the inner quasiquote: they are only
+    evaluated if there are as many unquotes as quasiquotes around them.
+
+    Arguments:  template - the quasiquoted expression
+                level - the number of quasiquotes around the template (1 for the outermost one)
+                environment - the environment unquoted expressions are evaluated in
+    Returns:    The filled template or an Error
+*/
+fn quasiquote(template: &RlType, level: usize, environment: &RlEnv) -> RlReturn {
+    match template {
+        RlType::List(content, _) => {
+            if let Some(RlType::Symbol(s)) = content.first() {
+                // (unquote x) is replaced by the value of x, inside of a nested quasiquote it stays
+                if s == "unquote" {
+                    check_arity("unquote", content.len() - 1, 1, Some(1))?;
+                    if level == 1 {
+                        return eval(content[1].clone(), environment.clone());
                     }
+                    let inner = quasiquote(&content[1], level - 1, environment)?;
+                    return Ok(list_from_vec(vec![content[0].clone(), inner]));
                 }
-            };
+                // a nested quasiquote needs one more unquote
+                if s == "quasiquote" {
+                    check_arity("quasiquote", content.len() - 1, 1, Some(1))?;
+                    let inner = quasiquote(&content[1], level + 1, environment)?;
+                    return Ok(list_from_vec(vec![content[0].clone(), inner]));
+                }
+            }
+            let mut result = Vec::new();
+            for element in content.iter() {
+                quasiquote_element(element, level, environment, &mut result)?;
+            }
+            return Ok(list_from_vec(result));
+        }
+        // a dotted template: the elements are filled like the ones of a list, the last cdr like a template
+        RlType::Pair(..) => {
+            let mut result = Vec::new();
+            let mut rest = template.clone();
+            while let RlType::Pair(car, cdr) = &rest {
+                quasiquote_element(&car.borrow(), level, environment, &mut result)?;
+                let next = cdr.borrow().clone();
+                rest = next;
+            }
+            // (a . ~@x) reads as (a unquote-splicing x), after the dot there is no list to splice into
+            if uncons(&rest).map(|(car, _)| car) == Some(RlType::Symbol("unquote-splicing".to_string())) {
+                return Err(RlErr::Syntax("unquote-splicing is not allowed after the dot of a list".to_string()));
+            }
+            let tail = quasiquote(&rest, level, environment)?;
+            return Ok(make_list(result, tail));
+        }
+        // atoms are not evaluated
+        _ => Ok(template.clone()),
+    }
+}
+
+/**
+    Fills one element of a quasiquoted list into the result: the elements of the value of
+    (unquote-splicing x) are inserted, every other element is filled like a template.
+
+    Arguments:  element - the element of the template
+                level - the number of quasiquotes around the element
+                environment - the environment unquoted expressions are evaluated in
+                result - the filled elements of the list so far
+    Returns:    Nothing or an Error
+*/
+fn quasiquote_element(
+    element: &RlType,
+    level: usize,
+    environment: &RlEnv,
+    result: &mut Vec<RlType>,
+) -> Result<(), RlErr> {
+    match element {
+        RlType::List(l, _) if l.first() == Some(&RlType::Symbol("unquote-splicing".to_string())) => {
+            check_arity("unquote-splicing", l.len() - 1, 1, Some(1))?;
+            // inside of a nested quasiquote the splice stays, filled one level less
+            if level > 1 {
+                let inner = quasiquote(&l[1], level - 1, environment)?;
+                result.push(list_from_vec(vec![l[0].clone(), inner]));
+                return Ok(());
+            }
+            let value = eval(l[1].clone(), environment.clone())?;
+            match list_to_vec(&value) {
+                Some(spliced) => result.extend(spliced),
+                None => return Err(type_error("a list to splice", &value)),
+            }
+        }
+        _ => result.push(quasiquote(element, level, environment)?),
+    }
+    return Ok(());
+}
+
+/**
+    Converts data that is evaluated as code (the result of a macro or the argument of eval): the proper
+    lists built of pairs are converted to lists like the reader creates them, also inside of nested
+    lists. Dotted lists stay pairs.
+
+    Arguments:  expression - the expression to convert
+    Returns:    the converted expression
+*/
+fn to_code(expression: &RlType) -> RlType {
+    return match expression {
+        RlType::List(l, location) => RlType::List(l.iter().map(to_code).collect(), location.clone()),
+        RlType::Pair(..) => match list_to_vec(expression) {
+            Some(elements) => RlType::List(elements.iter().map(to_code).collect(), None),
+            None => expression.clone(),
+        },
+        _ => expression.clone(),
+    };
+}
+
+/**
+    Expands a macro call: the unevaluated arguments are bound to the parameters of the macro and the
+    body of the macro is evaluated, which results in the expansion.
+
+    Arguments:  name - the name of the macro
+                environment - the environment the macro was defined in
+                params - the parameters of the macro
+                body - the body of the macro
+                args - the unevaluated arguments of the call
+    Returns:    the expansion as code or an Error
+*/
+fn expand(name: &str, environment: RlEnv, params: &[RlType], body: &RlType, args: &[RlType]) -> RlReturn {
+    // bind the unevaluated arguments to the parameters of the macro
+    let macro_environment = new_env_bound(Some(environment), name, params, args.to_vec())?;
+    return Ok(to_code(&eval(body.clone(), macro_environment)?));
+}
+
+/**
+    Checks if the given expression is a call of a macro (a list whose first element is a symbol that
+    is mapped to a macro). If so, the unevaluated arguments are bound to the parameters of the macro
+    and the body of the macro is evaluated, which results in the expansion.
+
+    Arguments:  expression - the expression that could be a macro call
+                environment - the environment the macro name is looked up in
+    Returns:    the expansion and true if a macro was expanded, the unchanged expression and false
+                otherwise. An Error if the expansion fails.
+*/
+fn macroexpand_1(expression: RlType, environment: &RlEnv) -> Result<(RlType, bool), RlErr> {
+    if let RlType::List(content, _) = &expression {
+        if let Some(RlType::Symbol(name)) = content.first() {
+            if let Ok(RlType::Macro { ref env, ref params, ref body }) = search(environment, name.to_string()) {
+                return Ok((expand(name, env.clone(), params, body, &content[1..])?, true));
+            }
         }
-        // If given expression is no List but a Symbol, look up symbol in environment
-        RlType::Symbol(s) => Ok(search(&environment, s)?),
-        // Else given expression is self-evaluating
-        _ => Ok(expression.clone()),
+    }
+    return Ok((expression, false));
+}
+
+/**
+    Expands the given expression with macroexpand_1 until it is no macro call anymore.
+
+    Arguments:  expression - the expression to expand
+                environment - the environment macro names are looked up in
+    Returns:    The fully expanded expression or an Error
+*/
+fn macroexpand(expression: RlType, environment: &RlEnv) -> RlReturn {
+    let mut expression = expression;
+    loop {
+        let (expansion, expanded) = macroexpand_1(expression, environment)?;
+        if !expanded {
+            return Ok(expansion);
+        }
+        expression = expansion;
     }
 }
 
@@ -197,30 +1922,23 @@ pub fn eval(expression: RlType, environment: RlEnv) -> RlReturn {
     This function is a helper for the eval function. It takes a list of expressions(that are already
     evaluated), treat the first expression as function and apply the given expressions to the function.
     Here we can ignore case of empty list because that case is caught in eval.
+    Builtins can call apply as well to call RLisp functions they get as arguments. Other functions than
+    builtins are applied by a new evaluation (see run).
 
     Arguments:  args - the list of expressions
+                environment - the environment of the caller, it is passed on to builtins
     Returns:    The value evaluated by the function application or an Error.
 */
-pub fn apply(args: Vec<RlType>) -> RlReturn {
-    let func = args[0].clone();
-    // check if first argument is a function
-    match func {
+pub fn apply(args: Vec<RlType>, environment: &RlEnv) -> RlReturn {
+    return match &args[0] {
         // if its a function that is defined in Rust(part of the StdLib) just call it with the arguments
         RlType::Func(i) => i(args[1..].to_vec()),
-        // if its a self defined function(in RLisp), evaluate the function body after binding parameters
-        RlType::SelfDefinedFunc {
-            env: stored_env,
-            params: temp_params,
-            body: temp_body,
-        } => {
-            let params = &*temp_params;
-            let body = &*temp_body;
-            // create function environment and bind given parameters to formal arguments of function
-            let function_environment =
-                new_env_bound(Some(stored_env.clone()), params.clone(), args[1..].to_vec())?;
-            // then evaluate function body with new environment
-            eval(body.clone(), function_environment.clone())
+        // builtins check the number of arguments before the closure is called
+        RlType::Builtin(b) => {
+            check_arity(&b.name, args.len() - 1, b.min_args, b.max_args)?;
+            (b.func)(&args[1..], environment)
         }
-        _ => Err(error("Expected Function to apply!")),
-    }
+        // self defined functions, primitives and continuations are evaluated on the stack (see run)
+        _ => run(Step::Apply(args, environment.clone())),
+    };
 }
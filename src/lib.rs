@@ -0,0 +1,248 @@
+/*
+lib.rs: The library of the RLisp Interpreter.
+
+The Interpreter can be embedded into other Rust programs: create an Interpreter, evaluate RLisp code
+with eval_str or eval_file and exchange values between Rust and RLisp with define and get. Values
+are converted between Rust and RLisp with From and TryFrom (see types.rs).
+The RLisp executable (main.rs) is a client of this library.
+*/
+
+// The code base deliberately uses explicit returns and indented lists in its /** */ doc blocks
+#![allow(clippy::needless_return, clippy::doc_overindented_list_items)]
+
+// hook in submodules (Defines module-structure)
+mod coroutine;
+pub mod env;
+pub mod eval;
+mod numeric;
+pub mod printer;
+pub mod reader;
+mod stdlib;
+pub mod types;
+mod utils;
+
+// load important functionality from submodules
+use env::{init_global, register, register_with_env, search, set, RlEnv};
+use eval::{eval, with_strict_booleans};
+use reader::FormReader;
+use std::cell::Cell;
+use std::fs;
+use types::{locate, RlErr, RlReturn, RlType};
+
+#[macro_use]
+extern crate lazy_static;
+
+/**
+    This function returns a vector of RLisp expressions, that define functions in RLisp itself.
+    Here Users can add definitions or commands that should be executed every time executing RLisp.
+
+    Returns: Vector of Strings that should contain RLisp expressions
+*/
+fn self_defined_prebuild() -> Vec<String> {
+    vec![
+        "(define caar (lambda (x) (car (car x))))".to_string(),
+        "(define cadr (lambda (x) (car (cdr x))))".to_string(),
+        "(define cddr (lambda (x) (cdr (cdr x))))".to_string(),
+        "(define caddr (lambda (x) (cadr (cdr x))))".to_string(),
+        "(define cadar (lambda (x) (cadr (car x))))".to_string(),
+        "(define caddar (lambda (x) (caddr (car x))))".to_string(),
+        "(define cddar (lambda (x) (cddr (car x))))".to_string(),
+        "(define caadar (lambda (x) (caar (cdr (car x)))))".to_string(),
+        "(define the-empty-stream '())".to_string(),
+        "(define stream-null? (lambda (s) (nil? s)))".to_string(),
+        "(define stream-car (lambda (s) (car s)))".to_string(),
+        "(define stream-cdr (lambda (s) (force (cdr s))))".to_string(),
+    ]
+}
+
+/**
+    An RLisp Interpreter. It holds a global environment with the stdlib and the self defined RLisp
+    functions loaded, all code evaluated by the Interpreter shares this environment.
+*/
+pub struct Interpreter {
+    env: RlEnv,
+    // the strict-boolean option, it is active while the Interpreter evaluates code
+    strict_booleans: Cell<bool>,
+}
+
+impl Interpreter {
+    /**
+        Creates a new Interpreter with a fresh global environment
+
+        Returns:    the new Interpreter
+    */
+    pub fn new() -> Interpreter {
+        // create a new global environment (stdlib already loaded)
+        let env = init_global();
+        // execute/evaluate self defined RLisp expressions
+        for definition in self_defined_prebuild() {
+            let _ = eval_forms(reader::read_forms(definition, "prebuild"), &env);
+        }
+        return Interpreter {
+            env,
+            strict_booleans: Cell::new(false),
+        };
+    }
+
+    /**
+        Evaluates all expressions in the given String in order
+
+        Arguments:  code - the RLisp code to evaluate
+        Returns:    the value of the last expression (#nil if there is none) or the Error of the
+                    first expression that fails
+    */
+    pub fn eval_str(&self, code: &str) -> RlReturn {
+        return with_strict_booleans(self.strict_booleans.get(), || {
+            eval_forms(reader::read_forms(code.to_string(), "string"), &self.env)
+        });
+    }
+
+    /**
+        Evaluates all expressions of a file in order (like the load special form)
+
+        Arguments:  filename - the name of the file to evaluate
+        Returns:    the value of the last expression (#nil if there is none), an RlErr::Io if the file
+                    could not be read or the Error of the first expression that fails
+    */
+    pub fn eval_file(&self, filename: &str) -> RlReturn {
+        return with_strict_booleans(self.strict_booleans.get(), || load(filename, self.env.clone()));
+    }
+
+    /**
+        Evaluates an expression that was already read
+
+        Arguments:  expression - the AST of the expression
+        Returns:    the value of the expression or an Error
+    */
+    pub fn eval(&self, expression: RlType) -> RlReturn {
+        return with_strict_booleans(self.strict_booleans.get(), || eval(expression, self.env.clone()));
+    }
+
+    /**
+        Maps a symbol to a value in the global environment, like define does. Rust values are
+        converted to RLisp values.
+
+        Arguments:  name - the symbol-name
+                    value - the value to map the symbol to
+    */
+    pub fn define(&self, name: &str, value: impl Into<RlType>) {
+        set(&self.env, name.to_string(), value.into());
+    }
+
+    /**
+        Registers a Rust closure as builtin function in the global environment. Unlike functions of
+        the stdlib, the closure can capture state.
+
+        Arguments:  name - the symbol-name of the builtin
+                    min_args - the minimal number of arguments
+                    max_args - the maximal number of arguments, None if there is no upper limit
+                    func - the closure that is called with the evaluated arguments
+    */
+    pub fn register(
+        &self,
+        name: &str,
+        min_args: usize,
+        max_args: Option<usize>,
+        func: impl Fn(&[RlType]) -> RlReturn + 'static,
+    ) {
+        register(&self.env, name, min_args, max_args, func);
+    }
+
+    /**
+        Registers a Rust closure that gets the environment of the caller as well. The closure can call
+        RLisp functions it gets as arguments with eval::apply, errors of these calls are returned with ?.
+
+        Arguments:  name - the symbol-name of the builtin
+                    min_args - the minimal number of arguments
+                    max_args - the maximal number of arguments, None if there is no upper limit
+                    func - the closure that is called with the evaluated arguments and the caller's
+                           environment
+    */
+    pub fn register_with_env(
+        &self,
+        name: &str,
+        min_args: usize,
+        max_args: Option<usize>,
+        func: impl Fn(&[RlType], &RlEnv) -> RlReturn + 'static,
+    ) {
+        register_with_env(&self.env, name, min_args, max_args, func);
+    }
+
+    /**
+        Sets the strict-boolean option. By default every value except #f and #nil counts as true in the
+        tests of conditionals (cond, if, when, unless, and, or, not and the predicates of filter and
+        remove). With the option set, a test that is no boolean raises a type Error instead.
+        The option is kept in a thread-local while the Interpreter evaluates something, so it belongs
+        to the evaluation and not to the functions: a function of this Interpreter that is called
+        while another Interpreter evaluates (e.g. from a builtin) uses the option of the other one.
+
+        Arguments:  strict - true if tests must be booleans
+    */
+    pub fn set_strict_booleans(&self, strict: bool) {
+        self.strict_booleans.set(strict);
+    }
+
+    /**
+        Looks up the value of a symbol in the global environment. The value can be converted to a
+        Rust value with TryFrom.
+
+        Arguments:  name - the symbol-name
+        Returns:    the value of the symbol or an RlErr::UnboundSymbol
+    */
+    pub fn get(&self, name: &str) -> RlReturn {
+        return search(&self.env, name.to_string());
+    }
+}
+
+/// An Interpreter created with default() is the same as one created with new()
+impl Default for Interpreter {
+    fn default() -> Interpreter {
+        return Interpreter::new();
+    }
+}
+
+/**
+    Takes a filename and reads the content as String
+
+    Arguments:  filename - the name of the file to read as String
+    Returns:    file-content as String or an RlErr::Io if the file could not be read
+*/
+fn read_file_string(filename: String) -> Result<String, RlErr> {
+    return fs::read_to_string(&filename).map_err(|err| RlErr::Io {
+        filename,
+        message: err.to_string(),
+    });
+}
+
+/**
+    Evaluates the expressions of a FormReader one after another, each expression is evaluated before
+    the next one is read. The evaluation stops at the first expression that can't be read or evaluated.
+
+    Arguments:  forms - the FormReader that reads the expressions
+                env - the environment, the expressions should be evaluated in
+    Returns:    the value of the last expression (#nil if there is none) or the Error of the failing
+                expression (with its location)
+*/
+fn eval_forms(mut forms: FormReader, env: &RlEnv) -> RlReturn {
+    let mut result = RlType::Nil;
+    // read the next expression (the location where it starts is needed before reading it)
+    while let (Some(location), Some(expression)) = (forms.location(), forms.next()) {
+        // evaluate it, errors without a location are located at the expression
+        result = eval(expression?, env.clone()).map_err(|err| locate(err, location))?;
+    }
+    return Ok(result);
+}
+
+/**
+    Takes a filename and an environment uses read_file_string to read string from file, then
+    evaluates the top-level expressions of the file one after another using eval_forms.
+
+    Arguments:  filename - name of file to read from
+                env - the environment, the loaded expressions should be evaluated in
+    Returns:    the value of the last expression (#nil for an empty file), an RlErr::Io if the file could
+                not be read or the Error of the failing expression (with its location in the file)
+*/
+pub(crate) fn load(filename: &str, env: RlEnv) -> RlReturn {
+    let file_string = read_file_string(filename.to_string())?;
+    return eval_forms(reader::read_forms(file_string, filename), &env);
+}
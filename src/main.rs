@@ -3,108 +3,102 @@ main.rs: The main file of the RLisp Interpreter.
 
 Use with Commandline-Argument: argument is interpreted as filename and try to load it
 Use without Commandline-Argument: Read-Eval-Print Loop is executed , User can input commands line by line
+Use with the option --strict-booleans (in both cases): the tests of conditionals must be booleans
 */
 
-// hook in submodules (Defines module-structure)
-mod env;
-mod eval;
-mod printer;
-mod reader;
-mod stdlib;
-pub mod types;
-pub mod utils;
+// The code base deliberately uses explicit returns and indented lists in its /** */ doc blocks
+#![allow(clippy::needless_return, clippy::doc_overindented_list_items)]
 
-// load important functionality from submodules
-use crate::env::RlEnv;
-use eval::eval;
-use std::fs;
-use types::{RlErr, RlReturn, RlType};
-
-#[macro_use]
-extern crate lazy_static;
+// load important functionality from the RLisp library
+use rlisp::printer;
+use rlisp::reader;
+use rlisp::types::{RlErr, RlReturn, RlType};
+use rlisp::Interpreter;
 
 extern crate rustyline;
 
-/**
-    This function returns a vector of RLisp expressions, that define functions in RLisp itself.
-    Here Users can add definitions or commands that should be executed every time executing RLisp.
-
-    Returns: Vector of Strings that should contain RLisp expressions
-*/
-fn self_defined_prebuild() -> Vec<String> {
-    vec![
-        "(define caar (lambda (x) (car (car x))))".to_string(),
-        "(define cadr (lambda (x) (car (cdr x))))".to_string(),
-        "(define cddr (lambda (x) (cdr (cdr x))))".to_string(),
-        "(define caddr (lambda (x) (cadr (cdr x))))".to_string(),
-        "(define cadar (lambda (x) (cadr (car x))))".to_string(),
-        "(define caddar (lambda (x) (caddr (car x))))".to_string(),
-        "(define cddar (lambda (x) (cddr (car x))))".to_string(),
-        "(define caadar (lambda (x) (caar (cdr (car x)))))".to_string(),
-    ]
-}
-
 /**
     main() is the entry-point into RLisp, it checks for cmd arguments and if there is an
     argument, try to load from file with this name, otherwise start REPL(Read-Eval-Print-Loop)
 */
 fn main() {
     let args: Vec<String> = std::env::args().collect();
-    // create a new global environment (stdlib already loaded)
-    let env = env::init_global();
-    // execute/evaluate self defined RLisp expressions
-    for definition in self_defined_prebuild().iter() {
-        rep_wrapper(definition, env.clone(), false);
-    }
+    // the option --strict-booleans can be given in front of or after the filename
+    let strict = args.iter().any(|arg| arg == "--strict-booleans");
+    let args: Vec<&String> = args.iter().filter(|arg| *arg != "--strict-booleans").collect();
+    // create a new Interpreter (stdlib and self defined RLisp expressions already loaded)
+    let interpreter = Interpreter::new();
+    interpreter.set_strict_booleans(strict);
 
     if args.len() == 2 {
-        // if argument was given open and operate on file input
-        load(&args[1], env.clone());
+        // if argument was given open and operate on file input, print the value of the last expression
+        print_result(interpreter.eval_file(args[1]).map(PRINT), true);
     } else {
         // else operate in cmd mode -> REPL
         let mut rl = rustyline::Editor::<()>::new();
+        // collects the lines of an expression that spans multiple lines
+        let mut input_buffer = String::new();
         loop {
-            // use extern crate rustyline, to get userinput
-            let input = rl.readline("user> ");
+            // use extern crate rustyline, to get userinput. While an expression is not complete, a
+            // continuation prompt is shown
+            let prompt = if input_buffer.is_empty() { "user> " } else { "  ... " };
+            let input = rl.readline(prompt);
             // check if there was a valid input
             match input {
                 // There was a valid input
                 Ok(line) => {
                     // if command == exit, break out of the REPL
-                    if line == "exit" {
+                    if input_buffer.is_empty() && line.trim() == "exit" {
                         break;
                     }
-                    // if command != exit, call the rep-wrapper with the global environment
-                    rep_wrapper(&line, env.clone(), true);
+                    input_buffer.push_str(&line);
+                    input_buffer.push('\n');
+                    match READ(&input_buffer) {
+                        // if the input ends in the middle of an expression, wait for the next line
+                        Err(RlErr::Incomplete(_)) => continue,
+                        // evaluate all expressions of the input in order with the global environment
+                        Ok(expressions) => {
+                            for expression in expressions {
+                                print_result(EVAL(expression, &interpreter).map(PRINT), true);
+                            }
+                        }
+                        Err(err) => print_result(Err(err), true),
+                    }
+                    input_buffer.clear();
+                }
+                // There was no valid input -> Give information, discard unfinished input and repeat the loop
+                Err(_) => {
+                    input_buffer.clear();
+                    println!("No input")
                 }
-                // There was no valid input -> Give information and repeat the loop
-                Err(_) => println!("No input"),
             }
         }
     }
 }
 
 /**
-    Calls reader to convert a string to an AST(Abstract Syntax tree). That is a language specific
+    Calls reader to convert a string to ASTs(Abstract Syntax trees). That is a language specific
     Data-structure that the Evaluator can work with internally.
 
-    Arguments:  plain_input - a String that should be parsed into an AST
-    Returns:    Of type RLReturn, so either a valid AST or an (Parsing)Error
+    Arguments:  plain_input - a String that should be parsed into ASTs, can contain several expressions
+    Returns:    either the valid ASTs of all expressions or an (Parsing)Error
 */
-fn READ(plain_input: &String) -> RlReturn {
-    return reader::read_str(plain_input.to_string());
+#[allow(non_snake_case)]
+fn READ(plain_input: &str) -> Result<Vec<RlType>, RlErr> {
+    return reader::read_all(plain_input.to_string(), "repl");
 }
 
 /**
-    Takes an AST and an Environment, calls eval::eval(The Interface to the Evaluator-Part of the Interpreter)
-    and returns the result of the Evaluator.
+    Takes an AST and an Interpreter, calls Interpreter::eval(The Interface to the Evaluator-Part of the
+    Interpreter) and returns the result of the Evaluator.
 
     Arguments:  expression - the AST(type RLType) that should be evaluated
-                env - the environment that the evaluator should work with
+                interpreter - the Interpreter that holds the environment the evaluator works with
     Returns:    of type RlReturn, so either the result or an (Evaluation)Error
 */
-fn EVAL(expression: RlType, env: RlEnv) -> RlReturn {
-    return eval(expression, env);
+#[allow(non_snake_case)]
+fn EVAL(expression: RlType, interpreter: &Interpreter) -> RlReturn {
+    return interpreter.eval(expression);
 }
 
 /**
@@ -113,74 +107,32 @@ fn EVAL(expression: RlType, env: RlEnv) -> RlReturn {
     Arguments:  text - the AST that should be converted to a string
     Returns:    the String to the given text
 */
+#[allow(non_snake_case)]
 fn PRINT(text: RlType) -> String {
     return printer::print_str(text);
 }
 
 /**
-   read-eval-print: Chain calls READ-EVAL-PRINT
-
-   Arguments:   to_process - an input string that should be interpreted
-                env - the environment the expression/input string is evaluated in
-   Returns:     Error? -> object with type RLError is returned, otherwise the result sting
-*/
-fn rep(to_process: &String, env: RlEnv) -> Result<String, RlErr> {
-    return Ok(PRINT(EVAL(READ(to_process)?, env)?));
-}
-
-/**
-    Interface to rep:
-    It calls rep with the string and the environment, print out results or errors nicely if print
-    flag is set.
+    Prints out the result of an evaluation or the error nicely if print flag is set.
 
-    Arguments:  to_rep - the string to process
-                env - the environment, that the Evaluator works with
+    Arguments:  result - the printed result of an evaluation or an Error
                 print_flag - determines whether the errors or results are printed out
-
-    Returns:    -
 */
-fn rep_wrapper(to_rep: &String, env: RlEnv, print_flag: bool) {
-    // if there was an input
-    if to_rep.len() > 0 {
-        // call rep to process input, then check if there was
-        match rep(&to_rep, env) {
-            Ok(res) => {
-                if print_flag {
-                    println!("{}", res)
-                }
+fn print_result(result: Result<String, RlErr>, print_flag: bool) {
+    match result {
+        Ok(res) => {
+            if print_flag {
+                println!("{}", res)
             }
-            Err(err) => {
-                if print_flag {
-                    println!("Exception! {}: ", err)
+        }
+        Err(err) => {
+            if print_flag {
+                println!("Exception! {}: ", err);
+                // show the RLisp functions that were called when the error occurred
+                if let Some(backtrace) = printer::print_backtrace(&err) {
+                    println!("{}", backtrace);
                 }
             }
         }
     }
 }
-
-/**
-    Takes a filename and reads the content as String, if an error occurs, NIL is returned
-
-    Arguments:  filename - the name of the file to read as String
-    Returns:    file-content as String
-*/
-fn read_file_string(filename: String) -> String {
-    return fs::read_to_string(filename).expect("#nil");
-}
-
-/**
-    Takes a filename and an environment uses read_file_string to read string from file, then
-    surrounds this string with a (do ) expression -> This is so that all expressions in the
-    file are executed even if there are multiple independent ones on multiple lines
-    (newlines are removed in the parsing process).
-
-    Arguments:  filename - name of file to read from
-                env - the environment, the loaded expressions should be evaluated in
-*/
-fn load(filename: &String, env: RlEnv) {
-    // load file string and pack into do expression
-    let file_string = read_file_string(filename.to_string());
-    let to_execute = format!("(do {})", file_string);
-    // execute prepared expression
-    rep_wrapper(&to_execute, env, true)
-}
@@ -0,0 +1,350 @@
+/*
+numeric.rs: holds the numeric tower of RLisp. It defines how the different kinds of numbers (Int, BigInt,
+            Rational and Float) are combined by the arithmetic functions of the stdlib. As soon as a Float is
+            involved in an operation, the other operand is converted to a Float as well (numeric contagion).
+            All other numbers are exact: When the result of an Int operation overflows, it is promoted to a
+            BigInt and BigInts are demoted to Ints again as soon as they fit. Dividing exact numbers results
+            in a Rational, which is turned back into an integer as soon as its denominator is 1.
+ */
+
+// load needed sibling-modules
+use crate::types::{type_error, RlErr, RlReturn, RlType};
+
+// load needed Rust-Functionality
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{ToPrimitive, Zero};
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+/**
+    Checks whether the given expression is a number.
+
+    Arguments:  expr - the expression to check
+    Returns:    true if expr is an Int, a BigInt, a Rational or a Float, false otherwise
+*/
+pub fn is_number(expr: &RlType) -> bool {
+    return matches!(
+        expr,
+        RlType::Int(_) | RlType::BigInt(_) | RlType::Rational(_) | RlType::Float(_)
+    );
+}
+
+/**
+    Checks whether the given expression is an exact integer.
+
+    Arguments:  expr - the expression to check
+    Returns:    true if expr is an Int or a BigInt, false otherwise
+*/
+pub fn is_exact_integer(expr: &RlType) -> bool {
+    return matches!(expr, RlType::Int(_) | RlType::BigInt(_));
+}
+
+/**
+    Checks whether the given expression is an exact number.
+
+    Arguments:  expr - the expression to check
+    Returns:    true if expr is an Int, a BigInt or a Rational, false otherwise
+*/
+pub fn is_exact(expr: &RlType) -> bool {
+    return matches!(expr, RlType::Int(_) | RlType::BigInt(_) | RlType::Rational(_));
+}
+
+/**
+    Turns a BigInt into a number of RLisp. If the value fits into an Int, it is demoted to an Int.
+
+    Arguments:  value - the BigInt value
+    Returns:    RlType::Int if value fits into an i64, RlType::BigInt otherwise
+*/
+pub fn normalize(value: BigInt) -> RlType {
+    return match value.to_i64() {
+        Some(i) => RlType::Int(i),
+        None => RlType::BigInt(Rc::new(value)),
+    };
+}
+
+/**
+    Turns a BigRational into a number of RLisp. If the denominator is 1, the value is turned into an
+    integer (see normalize).
+
+    Arguments:  value - the BigRational value (is always reduced by num_rational)
+    Returns:    an exact integer if value is integral, RlType::Rational otherwise
+*/
+pub fn normalize_rational(value: BigRational) -> RlType {
+    return if value.is_integer() {
+        normalize(value.to_integer())
+    } else {
+        RlType::Rational(Rc::new(value))
+    };
+}
+
+/**
+    Converts an exact integer to a BigInt value (needed to promote Ints on overflow).
+
+    Arguments:  number - the Int or BigInt to convert
+    Returns:    the value as BigInt or an Error if the given expression is no exact integer
+*/
+fn to_bigint(number: &RlType) -> Result<BigInt, RlErr> {
+    return match number {
+        RlType::Int(i) => Ok(BigInt::from(*i)),
+        RlType::BigInt(b) => Ok((**b).clone()),
+        _ => Err(type_error("an integer", number)),
+    };
+}
+
+/**
+    Converts an exact number to a BigRational value.
+
+    Arguments:  number - the Int, BigInt or Rational to convert
+    Returns:    the value as BigRational or an Error if the given expression is no exact number
+*/
+fn to_rational(number: &RlType) -> Result<BigRational, RlErr> {
+    return match number {
+        RlType::Rational(r) => Ok((**r).clone()),
+        _ => Ok(BigRational::from_integer(to_bigint(number)?)),
+    };
+}
+
+/**
+    Converts a number to a Float value (needed for numeric contagion).
+
+    Arguments:  number - the number to convert
+    Returns:    the value as f64 or an Error if the given expression is no number
+*/
+pub fn to_float(number: &RlType) -> Result<f64, RlErr> {
+    return match number {
+        RlType::Int(i) => Ok(*i as f64),
+        // BigInts that are too large for a f64 become infinite
+        RlType::BigInt(b) => Ok(b.to_f64().unwrap_or(f64::INFINITY)),
+        RlType::Rational(r) => Ok(r.to_f64().unwrap_or(f64::NAN)),
+        RlType::Float(f) => Ok(*f),
+        _ => Err(type_error("a number", number)),
+    };
+}
+
+/**
+    Adds two numbers. The result is exact if both numbers are exact, a Float otherwise.
+
+    Arguments:  a, b - the numbers to add
+    Returns:    the sum or an Error if one of the arguments is no number
+*/
+pub fn add(a: &RlType, b: &RlType) -> RlReturn {
+    // fast path: the operation on two Ints does not overflow
+    if let (RlType::Int(x), RlType::Int(y)) = (a, b) {
+        if let Some(result) = x.checked_add(*y) {
+            return Ok(RlType::Int(result));
+        }
+    }
+    if is_exact_integer(a) && is_exact_integer(b) {
+        return Ok(normalize(to_bigint(a)? + to_bigint(b)?));
+    }
+    if is_exact(a) && is_exact(b) {
+        return Ok(normalize_rational(to_rational(a)? + to_rational(b)?));
+    }
+    return Ok(RlType::Float(to_float(a)? + to_float(b)?));
+}
+
+/**
+    Subtracts the second number from the first one. The result is exact if both numbers are exact,
+    a Float otherwise.
+
+    Arguments:  a, b - the numbers to subtract
+    Returns:    the difference or an Error if one of the arguments is no number
+*/
+pub fn sub(a: &RlType, b: &RlType) -> RlReturn {
+    // fast path: the operation on two Ints does not overflow
+    if let (RlType::Int(x), RlType::Int(y)) = (a, b) {
+        if let Some(result) = x.checked_sub(*y) {
+            return Ok(RlType::Int(result));
+        }
+    }
+    if is_exact_integer(a) && is_exact_integer(b) {
+        return Ok(normalize(to_bigint(a)? - to_bigint(b)?));
+    }
+    if is_exact(a) && is_exact(b) {
+        return Ok(normalize_rational(to_rational(a)? - to_rational(b)?));
+    }
+    return Ok(RlType::Float(to_float(a)? - to_float(b)?));
+}
+
+/**
+    Multiplies two numbers. The result is exact if both numbers are exact, a Float otherwise.
+
+    Arguments:  a, b - the numbers to multiply
+    Returns:    the product or an Error if one of the arguments is no number
+*/
+pub fn mul(a: &RlType, b: &RlType) -> RlReturn {
+    // fast path: the operation on two Ints does not overflow
+    if let (RlType::Int(x), RlType::Int(y)) = (a, b) {
+        if let Some(result) = x.checked_mul(*y) {
+            return Ok(RlType::Int(result));
+        }
+    }
+    if is_exact_integer(a) && is_exact_integer(b) {
+        return Ok(normalize(to_bigint(a)? * to_bigint(b)?));
+    }
+    if is_exact(a) && is_exact(b) {
+        return Ok(normalize_rational(to_rational(a)? * to_rational(b)?));
+    }
+    return Ok(RlType::Float(to_float(a)? * to_float(b)?));
+}
+
+/**
+    Divides the first number by the second one. The division of two exact numbers is exact: If the
+    result is not integral, it is a Rational. Dividing an exact number by zero leads to an error.
+    As soon as a Float is involved, the result is a Float.
+
+    Arguments:  a - the dividend
+                b - the divisor
+    Returns:    the quotient or an Error if one of the arguments is no number or divided by exact 0
+*/
+pub fn div(a: &RlType, b: &RlType) -> RlReturn {
+    // fast path: two Ints that are divisible without remainder
+    if let (RlType::Int(x), RlType::Int(y)) = (a, b) {
+        if *y != 0 && x.checked_rem(*y) == Some(0) {
+            if let Some(result) = x.checked_div(*y) {
+                return Ok(RlType::Int(result));
+            }
+        }
+    }
+    if is_exact(a) && is_exact(b) {
+        let divisor = to_rational(b)?;
+        if divisor.is_zero() {
+            return Err(RlErr::DivByZero);
+        }
+        return Ok(normalize_rational(to_rational(a)? / divisor));
+    }
+    return Ok(RlType::Float(to_float(a)? / to_float(b)?));
+}
+
+/**
+    Performs a full number division of two exact integers. Depending on the given operation, the
+    quotient (rounded towards zero), the remainder (has the sign of the dividend) or the modulo
+    (has the sign of the divisor) is returned.
+
+    Arguments:  a - the dividend
+                b - the divisor
+                operation - "quotient", "remainder" or "modulo"
+    Returns:    the result of the division or an Error if one of the arguments is no exact integer
+                or the divisor is zero
+*/
+pub fn integer_division(a: &RlType, b: &RlType, operation: &str) -> RlReturn {
+    for number in [a, b] {
+        if !is_exact_integer(number) {
+            return Err(type_error(&format!("an integer for {}", operation), number));
+        }
+    }
+    let (dividend, divisor) = (to_bigint(a)?, to_bigint(b)?);
+    if divisor.is_zero() {
+        return Err(RlErr::DivByZero);
+    }
+    return Ok(normalize(match operation {
+        "quotient" => dividend / divisor,
+        "remainder" => dividend % divisor,
+        // the modulo takes the sign of the divisor
+        _ => ((dividend % &divisor) + &divisor) % &divisor,
+    }));
+}
+
+/**
+    Compares two numbers.
+
+    Arguments:  a, b - the numbers to compare
+    Returns:    the Ordering of a relative to b (None if a Float is NaN) or an Error if one of
+                the arguments is no number
+*/
+pub fn compare(a: &RlType, b: &RlType) -> Result<Option<Ordering>, RlErr> {
+    return match (a, b) {
+        (RlType::Int(x), RlType::Int(y)) => Ok(Some(x.cmp(y))),
+        _ if is_exact(a) && is_exact(b) => Ok(Some(to_rational(a)?.cmp(&to_rational(b)?))),
+        _ => Ok(to_float(a)?.partial_cmp(&to_float(b)?)),
+    };
+}
+
+/**
+    Applies a rounding function to a number. Exact integers are already integral and returned
+    unchanged, Rationals are rounded to exact integers and Floats are rounded and stay Floats.
+
+    Arguments:  number - the number to round
+                float_rounding - the function that rounds a f64 value (e.g. f64::floor)
+                rational_rounding - the function that rounds a BigRational value (e.g. BigRational::floor)
+    Returns:    the rounded number or an Error if the argument is no number
+*/
+pub fn round_with(
+    number: &RlType,
+    float_rounding: fn(f64) -> f64,
+    rational_rounding: fn(&BigRational) -> BigRational,
+) -> RlReturn {
+    return match number {
+        RlType::Int(_) | RlType::BigInt(_) => Ok(number.clone()),
+        RlType::Rational(r) => Ok(normalize_rational(rational_rounding(r))),
+        RlType::Float(f) => Ok(RlType::Float(float_rounding(*f))),
+        _ => Err(type_error("a number", number)),
+    };
+}
+
+/**
+    Rounds a BigRational to the nearest integer. If it is exactly between two integers, it is rounded
+    to the even one (like f64::round_ties_even does for Floats).
+
+    Arguments:  value - the BigRational to round
+    Returns:    the rounded value
+*/
+pub fn round_rational_ties_even(value: &BigRational) -> BigRational {
+    let floor = value.floor();
+    let half = BigRational::new(BigInt::from(1), BigInt::from(2));
+    return match (value - &floor).cmp(&half) {
+        Ordering::Less => floor,
+        Ordering::Greater => floor + BigInt::from(1),
+        // exactly in between: choose the even one of floor and floor + 1
+        Ordering::Equal if (floor.to_integer() % BigInt::from(2)).is_zero() => floor,
+        Ordering::Equal => floor + BigInt::from(1),
+    };
+}
+
+/**
+    Calculates the square root of a number. The square root of an exact number whose numerator and
+    denominator are perfect squares is exact again, in all other cases the result is a Float.
+
+    Arguments:  number - the number to calculate the square root of, must not be negative
+    Returns:    the square root or an Error if the argument is no number or negative
+*/
+pub fn sqrt(number: &RlType) -> RlReturn {
+    let value = to_float(number)?;
+    if value < 0.0 {
+        return Err(type_error("a non-negative number for sqrt", number));
+    }
+    if is_exact(number) {
+        // check if the roots of numerator and denominator are exact
+        let rational = to_rational(number)?;
+        let (numer_root, denom_root) = (rational.numer().sqrt(), rational.denom().sqrt());
+        if &numer_root * &numer_root == *rational.numer() && &denom_root * &denom_root == *rational.denom() {
+            return Ok(normalize_rational(BigRational::new(numer_root, denom_root)));
+        }
+    }
+    return Ok(RlType::Float(value.sqrt()));
+}
+
+/**
+    Returns the numerator or the denominator of a number. Integers have the denominator 1. For Floats,
+    the numerator or denominator of the exact value of the Float is returned as Float.
+
+    Arguments:  number - the number to get the numerator or denominator of
+                numerator - true to get the numerator, false to get the denominator
+    Returns:    the numerator or denominator or an Error if the argument is no number or an infinite Float
+*/
+pub fn numerator_denominator(number: &RlType, numerator: bool) -> RlReturn {
+    let rational = match number {
+        RlType::Float(f) => match BigRational::from_float(*f) {
+            Some(r) => r,
+            None => return Err(type_error("a finite number", number)),
+        },
+        _ => to_rational(number)?,
+    };
+    let part = if numerator { rational.numer().clone() } else { rational.denom().clone() };
+    return match number {
+        // Floats stay inexact
+        RlType::Float(_) => Ok(RlType::Float(part.to_f64().unwrap_or(f64::INFINITY))),
+        _ => Ok(normalize(part)),
+    };
+}
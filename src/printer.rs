@@ -3,8 +3,8 @@ printer.rs: Holds functions to transform an expression (as RlType (AST)) back to
             This is needed to make the results of the evaluator readable for a User.
 */
 
-// load needed sibling modules, here we just need the AST-Type
-use super::types::RlType;
+// load needed sibling modules, here we just need the AST-Type and the Error-Type
+use super::types::{RlErr, RlType};
 
 /**
     This is the Interface that is used to convert an expression(as AST-Type) to a String.
@@ -27,26 +27,88 @@ pub fn print_str(expression: RlType) -> String {
 */
 pub fn print_str_rec(expression: RlType) -> String {
     // check which type the given expression has
-    return match expression {
+    return match &expression {
         RlType::Int(value) => format!("{}", value),
-        RlType::Symbol(i) => format!("{}", i),
-        RlType::String(i) => format!("{}", i),
+        RlType::BigInt(value) => format!("{}", value),
+        RlType::Rational(value) => format!("{}", value),
+        // Debug formatting keeps the decimal point of integral Floats (3.0 instead of 3)
+        RlType::Float(value) => format!("{:?}", value),
+        RlType::Symbol(i) => i.to_string(),
+        RlType::String(i) => i.to_string(),
         // if we have list type, make recursive call to stringify elements of the list and surround
         // the elements that are separated by commas with ()-brackets
-        RlType::List(vec) => {
-            let iter: Vec<String> = vec.into_iter().map(print_str_rec).collect();
+        RlType::List(vec, _) => {
+            let iter: Vec<String> = vec.iter().cloned().map(print_str_rec).collect();
             let owned: String = format!("({})", iter.join(" "));
             owned
         }
+        // chains of pairs are printed like lists, if the last cdr is not a list it is separated by a dot
+        RlType::Pair(..) => {
+            let mut elements: Vec<String> = vec![];
+            let mut rest = expression.clone();
+            loop {
+                rest = match &rest {
+                    RlType::Pair(car, cdr) => {
+                        elements.push(print_str_rec(car.borrow().clone()));
+                        cdr.borrow().clone()
+                    }
+                    RlType::List(vec, _) => {
+                        elements.extend(vec.iter().cloned().map(print_str_rec));
+                        break;
+                    }
+                    tail => {
+                        elements.push(format!(". {}", print_str_rec(tail.clone())));
+                        break;
+                    }
+                };
+            }
+            format!("({})", elements.join(" "))
+        }
         RlType::Bool(b) => {
-            if b {
+            if *b {
                 String::from("#t")
             } else {
                 String::from("#f")
             }
         }
         // Function definitions are nit printed out, just labeled with #function
-        RlType::Func(_) | RlType::SelfDefinedFunc { .. } => String::from("#function"),
+        RlType::Func(_) | RlType::Builtin(_) | RlType::Primitive(_) | RlType::SelfDefinedFunc { .. } => {
+            String::from("#function")
+        }
+        RlType::Macro { .. } => String::from("#macro"),
+        // boxes are labeled with #box and their content
+        RlType::Box(content) => format!("#box<{}>", print_str_rec(content.borrow().clone())),
+        // continuations, coroutines and promises are just labeled with their type
+        RlType::Continuation(_) => String::from("#continuation"),
+        RlType::Coroutine(_) => String::from("#coroutine"),
+        RlType::Promise(_) => String::from("#promise"),
+        // error objects are labeled with #error and their message
+        RlType::Error(err) => format!("#error<{}>", err),
         RlType::Nil => String::from("#nil"),
     };
 }
+
+/**
+    Builds a readable backtrace of the RLisp call frames attached to an error. Every frame is shown
+    as a call of the function with its arguments, the innermost call first.
+
+    Arguments:  err - the error whose call frames should be printed
+    Returns:    the backtrace as String, or None if there are no frames attached to the error
+*/
+pub fn print_backtrace(err: &RlErr) -> Option<String> {
+    let trace = match err {
+        RlErr::Traced { trace, .. } if !trace.is_empty() => trace,
+        _ => return None,
+    };
+    let mut lines = vec![String::from("Backtrace (most recent call first):")];
+    for frame in trace.iter() {
+        // anonymous functions are labeled with #function
+        let mut call = vec![frame.name.clone().unwrap_or_else(|| String::from("#function"))];
+        call.extend(frame.args.iter().cloned().map(print_str_rec));
+        lines.push(match &frame.location {
+            Some(location) => format!("    at ({}) in {}", call.join(" "), location),
+            None => format!("    at ({})", call.join(" ")),
+        });
+    }
+    return Some(lines.join("\n"));
+}
@@ -5,33 +5,102 @@ reader.rs:  Holds the parser for S-Expressions, that is used to build up the Syn
 */
 
 // load needed Rust-Functionality
+use num_rational::BigRational;
 use regex::Regex;
+use std::rc::Rc;
 
 // load needed sibling-modules
-use super::types::{error, RlErr, RlReturn, RlType};
+use super::numeric;
+use super::types::{make_list, to_data, Location, RlErr, RlReturn, RlType};
 use super::utils;
-use crate::types::RlErr::ErrString;
 
 /**
-    This is the only function visible and the Interface of the whole
-    reader functionality. It takes a String and returns the according SyntaxTree(RLType).
-    If there are errors while parsing, an RLError is returned.
+    This is the Interface of the whole reader functionality. It takes a String and returns the
+    SyntaxTree(RLType) of the first expression in it. If there are errors while parsing, an RLError is returned.
 
     Arguments:  string - the string that will be parsed into an RLType.
+                file - the name of the file the string comes from, used for the locations of the lists
     Returns:    ParserError -> return RLError, resulting Syntax Tree otherwise
 */
-pub fn read_str(string: String) -> RlReturn {
+pub fn read_str(string: String, file: &str) -> RlReturn {
     // first tokenize the string -> list with valid RLisp tokens
-    let tokens = tokenize(&string);
+    let tokens = tokenize(&string, file);
     // if no tokens found, return error
     if tokens.is_empty() {
-        return Err(error("No valid tokens found"));
+        return Err(RlErr::Parse("No valid tokens found".to_string()));
     }
     //println!("Got tokens: {:?}", tokens);
     // use read_from_tokens to build up Syntax-tree from tokens
     return read_from_tokens(&mut Reader::new(tokens));
 }
 
+/**
+    Like read_str, but reads all expressions in the given String (e.g. the content of a file).
+
+    Arguments:  string - the string that will be parsed
+                file - the name of the file the string comes from, used for the locations of the lists
+    Returns:    the Syntax Trees of all expressions in order, or an RLError if one of them can't be parsed
+*/
+pub fn read_all(string: String, file: &str) -> Result<Vec<RlType>, RlErr> {
+    return read_forms(string, file).collect();
+}
+
+/**
+    Creates a FormReader that reads the expressions in the given String one after another.
+
+    Arguments:  string - the string that will be parsed
+                file - the name of the file the string comes from, used for the locations of the lists
+    Returns:    the FormReader
+*/
+pub fn read_forms(string: String, file: &str) -> FormReader {
+    return FormReader {
+        reader: Reader::new(tokenize(&string, file)),
+    };
+}
+
+/**
+    FormReader is an Iterator over the top-level expressions of a String (e.g. the content of a file),
+    so every expression can be evaluated before the next one is read.
+*/
+pub struct FormReader {
+    reader: Reader,
+}
+
+impl FormReader {
+    /**
+        Returns the location where the next expression starts
+
+        Returns:    the location of the next expression, None if there are no more expressions
+    */
+    pub fn location(&self) -> Option<Rc<Location>> {
+        return self.reader.location().ok();
+    }
+}
+
+impl Iterator for FormReader {
+    type Item = RlReturn;
+
+    /**
+        Reads the next expression
+
+        Returns:    the next expression or an RLError if it can't be parsed, None if there are no
+                    more expressions
+    */
+    fn next(&mut self) -> Option<RlReturn> {
+        let start = self.reader.position;
+        if start >= self.reader.tokens.len() {
+            return None;
+        }
+        let expression = read_from_tokens(&mut self.reader);
+        // skip the token that could not be read (e.g. a stray closing bracket), so the next call reads on
+        // after it instead of reporting it again
+        if expression.is_err() && self.reader.position == start {
+            self.reader.position += 1;
+        }
+        return Some(expression);
+    }
+}
+
 /**
     Takes a Reader-Instance and creates a Syntax-Tree(RLType) with the help of it.
 
@@ -47,7 +116,19 @@ fn read_from_tokens(reader: &mut Reader) -> RlReturn {
         // if token is double quote, we expect a String and call read_string()
         "[" => read_string(reader),
         // if token is simple quote ', read a quoted expression
-        "'" => read_quote(reader),
+        "'" => read_quote(reader, "quote"),
+        // if token is a backtick, read a quasiquoted expression
+        "`" => read_quote(reader, "quasiquote"),
+        // if token is ~ or ~@, read an unquoted expression (only meaningful inside a quasiquote)
+        "~" => read_quote(reader, "unquote"),
+        "~@" => read_quote(reader, "unquote-splicing"),
+        // closing brackets are only valid at the end of a list or string, a dot only in front of
+        // the last element of a list
+        ")" | "]" | "." => Err(RlErr::Parse(format!(
+            "unexpected {} at {}",
+            peeked_token,
+            reader.location()?
+        ))),
         // else we expect Atomic Elements
         _ => read_atom(reader),
     }
@@ -62,12 +143,15 @@ fn read_from_tokens(reader: &mut Reader) -> RlReturn {
 fn read_string(reader: &mut Reader) -> RlReturn {
     // create new empty String instance
     let mut string_tokens: Vec<String> = vec![];
-    // skip opening quote
+    // skip opening quote, remember where it is to report unclosed strings
+    let start = reader.location()?;
     reader.next()?;
     // loop until closing quote is found
     loop {
         // return error if suddenly EOF occurs
-        let token = reader.peek()?;
+        let token = reader
+            .peek()
+            .map_err(|_| RlErr::Incomplete(format!("unclosed string opened at {}", start)))?;
         if token == "]" {
             // if closing quote occurs, stop adding tokens to string
             reader.next()?;
@@ -81,44 +165,82 @@ fn read_string(reader: &mut Reader) -> RlReturn {
 
 /**
     Takes a Reader-Instance and collects a quoted expression from the token list. Therefore just
-    the first expression after the quoting symbol ' is read and wrapped by a "quote" special form.
+    the first expression after the quoting symbol (', `, ~ or ~@) is read and wrapped by the special
+    form with the given name.
 
     Arguments:  reader - the Reader Instance that holds the token list and current position in token-list
-    Returns:    resulting AST of form (quote {following expression}), If an Error occurs -> RLError
+                form - name of the special form to wrap the expression with (e.g. "quote" for ')
+    Returns:    resulting AST of form ({form} {following expression}), If an Error occurs -> RLError
 */
-fn read_quote(reader: &mut Reader) -> RlReturn {
-    // skip quoting symbol
-    reader.next()?;
-    // pack following expression in a quote operation -> (quote {following_expression})
-    return Ok(RlType::List(vec![RlType::Symbol("quote".to_string()), read_from_tokens(reader)?]));
+fn read_quote(reader: &mut Reader, form: &str) -> RlReturn {
+    // skip quoting symbol, the resulting list is located at the quoting symbol
+    let start = reader.location()?;
+    let symbol = reader.next()?;
+    if reader.peek().is_err() {
+        return Err(RlErr::Incomplete(format!("expected an expression after {} at {}", symbol, start)));
+    }
+    // quoted data is read as pairs, so it can be taken apart and changed like lists built with list
+    let mut expression = read_from_tokens(reader)?;
+    if form == "quote" {
+        expression = to_data(&expression);
+    }
+    // pack following expression in the given form -> ({form} {following_expression})
+    return Ok(RlType::List(vec![RlType::Symbol(form.to_string()), expression], Some(start)));
 }
 
 /**
     Takes a Reader-Instance and collects a list from the token list until a closing bracket is found.
-    Internally uses recursive calls to resolve the elements of the list.
+    Internally uses recursive calls to resolve the elements of the list. If a dot is found in front of
+    the last element, the list is a dotted list: (a b . c) is read as chain of pairs with c as last cdr.
 
     Arguments:  reader - the Reader Instance that holds the token list and current position in token-list
-    Returns:    A RLType::List, holding the read list, If an Error occurs -> RLError
+    Returns:    A RLType::List, holding the read list, a RlType::Pair for a dotted list,
+                If an Error occurs -> RLError
 */
 fn read_list(reader: &mut Reader) -> RlReturn {
     // create vector that will contain the list-elements
     let mut my_list: Vec<RlType> = vec![];
-    // skip opening bracket
+    // skip opening bracket, remember where it is to report unclosed lists
+    let start = reader.location()?;
     reader.next()?;
     // loop until closing bracket occurs
     loop {
         // return error if suddenly EOF occurs
-        let token = reader.peek()?;
+        let token = reader
+            .peek()
+            .map_err(|_| RlErr::Incomplete(format!("unclosed list opened at {}", start)))?;
         if token == ")" {
             // If closing bracket occurs, stop adding list-element to the vector
             reader.next()?;
             break;
         }
+        if token == "." && !my_list.is_empty() {
+            // skip the dot, the expression after it is the cdr of the last pair
+            let dot = reader.location()?;
+            reader.next()?;
+            if reader.peek()? == ")" {
+                return Err(RlErr::Parse(format!("expected an expression after . at {}", dot)));
+            }
+            let tail = read_from_tokens(reader)?;
+            // the list has to be closed right after the cdr
+            let closing = reader
+                .peek()
+                .map_err(|_| RlErr::Incomplete(format!("unclosed list opened at {}", start)))?;
+            if closing != ")" {
+                return Err(RlErr::Parse(format!("expected ) after the expression following . at {}", dot)));
+            }
+            reader.next()?;
+            return Ok(make_list(my_list, tail));
+        }
         // push list element. List element is return value of read_from_tokens() this is needed to
         // capture nested lists/expressions.
         my_list.push(read_from_tokens(reader)?);
     }
-    return Ok(RlType::List(my_list));
+    // the argument of (quote x) is read as pairs, like the one of 'x
+    if my_list.len() == 2 && my_list[0] == RlType::Symbol("quote".to_string()) {
+        my_list[1] = to_data(&my_list[1]);
+    }
+    return Ok(RlType::List(my_list, Some(start)));
 }
 
 /**
@@ -132,7 +254,15 @@ fn read_atom(reader: &mut Reader) -> RlReturn {
     let atom = reader.next()?.clone();
     // check if token represents an Integer
     return if utils::string_is_integer(atom.clone()) {
-        Ok(RlType::Int(atom.parse().unwrap()))
+        // Integers that don't fit into an i64 become BigInts
+        Ok(numeric::normalize(atom.parse().unwrap()))
+    } else if utils::string_is_rational(atom.clone()) {
+        // check if token represents a Rational, it is normalized (e.g. 4/2 becomes 2)
+        let rational: BigRational = atom.parse().map_err(|_| RlErr::Parse("denominator of a rational must not be 0".to_string()))?;
+        Ok(numeric::normalize_rational(rational))
+    } else if utils::string_is_float(atom.clone()) {
+        // check if token represents a Float
+        Ok(RlType::Float(atom.parse().unwrap()))
     } else if atom == "#t" {
         // token #t represents true
         Ok(RlType::Bool(true))
@@ -148,13 +278,22 @@ fn read_atom(reader: &mut Reader) -> RlReturn {
     };
 }
 
+/**
+    A token of the source code together with its location.
+*/
+struct Token {
+    text: String,
+    location: Rc<Location>,
+}
+
 /**
     Takes a string and extracts all valid tokens, that are important for RLisp.
 
     Arguments:  str - the string to tokenize
-    Returns:    a list of strings(tokens)
+                file - the name of the file the string comes from
+    Returns:    a list of tokens with their locations
 */
-pub fn tokenize(str: &str) -> Vec<String> {
+fn tokenize(str: &str, file: &str) -> Vec<Token> {
     // global immutable initialized at runtime -> Initialize regex to capture tokens(taken from MAL)
     lazy_static! {
         static ref RE: Regex = Regex::new(
@@ -163,27 +302,46 @@ pub fn tokenize(str: &str) -> Vec<String> {
         .unwrap();
     }
 
+    let file: Rc<str> = Rc::from(file);
+    // line of the current token and the offset of the start of this line
+    let (mut line, mut line_start, mut scanned) = (1, 0, 0);
     let mut res = vec![];
     // iterate over regex captures(=tokens)
     for cap in RE.captures_iter(str) {
+        let token = cap.get(1).unwrap();
         // if capture starts with ; its a comment, so dont add it
-        if cap[1].starts_with(";") {
+        if token.as_str().starts_with(';') {
             continue;
         }
+        // count the lines up to the start of the token
+        for (offset, char) in str[scanned..token.start()].char_indices() {
+            if char == '\n' {
+                line += 1;
+                line_start = scanned + offset + 1;
+            }
+        }
+        scanned = token.start();
         // else add this token to the list
-        res.push(String::from(&cap[1]));
+        res.push(Token {
+            text: String::from(token.as_str()),
+            location: Rc::new(Location {
+                file: file.clone(),
+                line,
+                column: str[line_start..token.start()].chars().count() + 1,
+            }),
+        });
     }
     return res;
 }
 
 /**
     Reader struct is a helper struct to operate on tokenized inputs.
-    It holds a list of tokens(Strings) and the current position in the token list (needed so that
+    It holds a list of tokens and the current position in the token list (needed so that
     reader functions can work without passing list-positions).
 */
 struct Reader {
     position: usize,
-    tokens: Vec<String>,
+    tokens: Vec<Token>,
 }
 
 impl Reader {
@@ -193,7 +351,7 @@ impl Reader {
         Arguments:  tokens -  list of tokens to add to the reader
         Returns:    a new reader with position 0 and the given tokens
     */
-    fn new(tokens: Vec<String>) -> Reader {
+    fn new(tokens: Vec<Token>) -> Reader {
         // initialize with position 0
         return Reader {
             position: 0,
@@ -211,10 +369,25 @@ impl Reader {
         return Ok(self
             .tokens
             .get(self.position)
-            .ok_or(ErrString("nothing to peek".to_string()))?
+            .ok_or_else(|| RlErr::Incomplete("unexpected end of input".to_string()))?
+            .text
             .to_string());
     }
 
+    /**
+        Returns the location of the token at current position
+
+        Returns:    if there is no token RLError, else the location of the token at current position
+    */
+    fn location(&self) -> Result<Rc<Location>, RlErr> {
+        return Ok(self
+            .tokens
+            .get(self.position)
+            .ok_or_else(|| RlErr::Incomplete("unexpected end of input".to_string()))?
+            .location
+            .clone());
+    }
+
     /**
         Returns token at current position and moves to next position.
 
@@ -227,7 +400,8 @@ impl Reader {
         return Ok(self
             .tokens
             .get(self.position - 1)
-            .ok_or(ErrString("nothing to peek".to_string()))?
+            .ok_or_else(|| RlErr::Incomplete("unexpected end of input".to_string()))?
+            .text
             .to_string());
     }
 }
@@ -4,8 +4,21 @@ stdlib.rs: holds the definition of all non-special form language atoms of RLisp
  */
 
 // load needed sibling-modules
-use crate::printer::{print_str_rec};
-use crate::types::{error, is_atom, RlErr, RlType};
+use crate::coroutine::Coroutine;
+use crate::eval::truth;
+use crate::numeric;
+use crate::printer::print_str_rec;
+use crate::types::{
+    builtin, builtin_with_env, check_arity, is_atom, list_from_vec, list_to_vec, make_pair, type_error, uncons,
+    untraced, value_to_error, Primitive, Promise, RlErr, RlReturn, RlType,
+};
+
+// load needed Rust-Functionality
+use num_rational::BigRational;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::rc::Rc;
 
 /**
     Is the Interface to the whole stdlib. It simply returns a mapping from symbol-names to RLisp
@@ -19,27 +32,82 @@ pub fn core() -> Vec<(&'static str, RlType)> {
         ("cdr", cdr()),
         ("cons", cons()),
         ("list", RlType::Func(|a| Ok(list(a)))),
-        ("+", integer_arithmetics("+")),
-        ("-", integer_arithmetics("-")),
-        ("*", integer_arithmetics("*")),
-        ("/", integer_arithmetics("/")),
-        (">", integer_arithmetics(">")),
-        ("<", integer_arithmetics("<")),
+        ("set-car!", mutation("set-car!")),
+        ("set-cdr!", mutation("set-cdr!")),
+        ("box", mutation("box")),
+        ("unbox", mutation("unbox")),
+        ("set-box!", mutation("set-box!")),
+        ("box?", mutation("box?")),
+        ("call/cc", RlType::Primitive(Primitive::CallCc)),
+        ("call-with-current-continuation", RlType::Primitive(Primitive::CallCc)),
+        ("dynamic-wind", RlType::Primitive(Primitive::DynamicWind)),
+        ("force", RlType::Primitive(Primitive::Force)),
+        ("make-promise", make_promise()),
+        ("coroutine", coroutine_function("coroutine")),
+        ("resume", coroutine_function("resume")),
+        ("yield", coroutine_function("yield")),
+        ("coroutine-done?", coroutine_function("coroutine-done?")),
+        ("make-generator", coroutine_function("make-generator")),
+        ("map", RlType::Primitive(Primitive::Map)),
+        ("for-each", RlType::Primitive(Primitive::ForEach)),
+        ("filter", RlType::Primitive(Primitive::Filter)),
+        ("remove", RlType::Primitive(Primitive::Remove)),
+        ("reduce", RlType::Primitive(Primitive::Reduce)),
+        ("fold-left", RlType::Primitive(Primitive::FoldLeft)),
+        ("fold-right", RlType::Primitive(Primitive::FoldRight)),
+        ("apply", RlType::Primitive(Primitive::Apply)),
+        ("append", list_function("append")),
+        ("reverse", list_function("reverse")),
+        ("length", list_function("length")),
+        ("nth", list_function("nth")),
+        ("last", list_function("last")),
+        ("range", list_function("range")),
+        ("iota", list_function("iota")),
+        ("+", arithmetics("+")),
+        ("-", arithmetics("-")),
+        ("*", arithmetics("*")),
+        ("/", arithmetics("/")),
+        (">", arithmetics(">")),
+        ("<", arithmetics("<")),
+        ("quotient", arithmetics("quotient")),
+        ("remainder", arithmetics("remainder")),
+        ("modulo", arithmetics("modulo")),
+        ("sqrt", math_function("sqrt")),
+        ("exp", math_function("exp")),
+        ("floor", math_function("floor")),
+        ("round", math_function("round")),
+        ("truncate", math_function("truncate")),
+        ("numerator", math_function("numerator")),
+        ("denominator", math_function("denominator")),
+        ("exact->inexact", math_function("exact->inexact")),
+        ("raise", error_function("raise")),
+        ("error-object?", error_function("error-object?")),
+        ("error-kind", error_function("error-kind")),
+        ("error-message", error_function("error-message")),
+        ("error-payload", error_function("error-payload")),
         ("eq?", equals()),
+        ("not", builtin("not", 1, Some(1), |a| Ok(RlType::Bool(!truth(&a[0])?)))),
         ("nil?", type_check("nil")),
-        ("number?", type_check("int")),
+        ("number?", type_check("number")),
+        ("integer?", type_check("integer")),
+        ("float?", type_check("float")),
         ("list?", type_check("list")),
+        ("pair?", type_check("pair")),
+        ("promise?", type_check("promise")),
         (
             "println",
             RlType::Func(|a| {
-                if a.len() != 1 {return Err(error("println takes exactly one argument"))}
+                check_arity("println", a.len(), 1, Some(1))?;
                 println!("{}", print_str_rec(a[0].clone()));
                 Ok(a[0].clone())
             }),
         ),
         (
             "atom?",
-            RlType::Func(|a| Ok(RlType::Bool(is_atom(a[0].clone())))),
+            RlType::Func(|a| {
+                check_arity("atom?", a.len(), 1, Some(1))?;
+                Ok(RlType::Bool(is_atom(a[0].clone())))
+            }),
         ),
     ]
 }
@@ -56,207 +124,581 @@ fn type_check(typ: &str) -> RlType {
     match typ {
         // return function for nil-typecheck
         "nil" => RlType::Func(|arg| {
+            check_arity("nil?", arg.len(), 1, Some(1))?;
             Ok(RlType::Bool(match &arg[0] {
                 RlType::Nil => true,
-                RlType::List(l) if l.len() == 0 => true,
+                RlType::List(l, _) if l.is_empty() => true,
                 _ => false,
             }))
         }),
-        // return function for Integer-typecheck
-        "int" => RlType::Func(|arg| {
+        // return function for number-typecheck (Int, BigInt, Rational or Float)
+        "number" => RlType::Func(|arg| {
+            check_arity("number?", arg.len(), 1, Some(1))?;
+            Ok(RlType::Bool(numeric::is_number(&arg[0])))
+        }),
+        // return function for Integer-typecheck, Floats with an integral value are integers as well
+        "integer" => RlType::Func(|arg| {
+            check_arity("integer?", arg.len(), 1, Some(1))?;
             Ok(RlType::Bool(match arg[0] {
-                RlType::Int(..) => true,
+                RlType::Int(..) | RlType::BigInt(..) => true,
+                RlType::Float(f) => f.fract() == 0.0,
                 _ => false,
             }))
         }),
-        // return function for List-typecheck
+        // return function for Float-typecheck
+        "float" => RlType::Func(|arg| {
+            check_arity("float?", arg.len(), 1, Some(1))?;
+            Ok(RlType::Bool(matches!(arg[0], RlType::Float(..))))
+        }),
+        // return function for Pair-typecheck (non-empty lists are pairs as well)
+        "pair" => RlType::Func(|arg| {
+            check_arity("pair?", arg.len(), 1, Some(1))?;
+            Ok(RlType::Bool(uncons(&arg[0]).is_some()))
+        }),
+        // return function for Promise-typecheck
+        "promise" => RlType::Func(|arg| {
+            check_arity("promise?", arg.len(), 1, Some(1))?;
+            Ok(RlType::Bool(matches!(arg[0], RlType::Promise(..))))
+        }),
+        // return function for List-typecheck, just proper lists (ending with the empty list) are lists
         _ => RlType::Func(|arg| {
-            Ok(RlType::Bool(match arg[0] {
-                RlType::List(..) => true,
-                _ => false,
-            }))
+            check_arity("list?", arg.len(), 1, Some(1))?;
+            Ok(RlType::Bool(list_to_vec(&arg[0]).is_some()))
         }),
     }
 }
 
 /**
-    Function exists for readability. It returns a function of type RLType::Func that performs Integer-
-    Arithmetics. The specific function returned depends on the parameter. (Options: +.-,*,/)
+    Function exists for readability. It returns a function of type RLType::Func that performs
+    Arithmetics on numbers. The specific function returned depends on the parameter. (Options: +.-,*,/,
+    quotient, remainder, modulo)
+    How the different kinds of numbers are combined is defined in numeric.rs.
 
     Arguments: sym - the symbol of the Arithmetic Operation. Determines which function is returned
     Returns: a Function of type RlType::Func performing an Arithmetic Operation
 */
-fn integer_arithmetics(sym: &str) -> RlType {
+fn arithmetics(sym: &str) -> RlType {
     // check which function is wanted
     match sym {
-        // return function for addition (takes a list of integers and returns its sum)
+        // return function for addition (takes a list of numbers and returns its sum)
         "+" => RlType::Func(|a: Vec<RlType>| {
-            let x = check_int_vector(a)?;
-            if x.len() < 1 {return Err(error("+ needs at least 1 parameter"));}
-            return Ok(RlType::Int(x.to_vec().iter().sum()));
+            let x = check_number_vector(a)?;
+            check_arity("+", x.len(), 1, None)?;
+            return sum(&x);
         }),
-        // return function for division(takes a list of integers and returns value of first
-        // element divided by sum of rest elements), needs at least 2 args
+        // return function for division(takes a list of numbers and divides the first element by
+        // each of the rest elements in order). Needs at least one parameter (with 1 arg returns 1/arg)
         "/" => RlType::Func(|a: Vec<RlType>| {
-            let x = check_int_vector(a)?;
-            if x.len() < 2 {return Err(error("/ needs min 2 params"))}
-            let divisor: i64 = x[1..].to_vec().iter().sum();
-            if divisor == 0 {
-                Err(error("cant divide by zero"))
-            } else {
-                Ok(RlType::Int(x[0] / divisor))
+            let x = check_number_vector(a)?;
+            check_arity("/", x.len(), 1, None)?;
+            if x.len() == 1 {
+                return numeric::div(&RlType::Int(1), &x[0]);
             }
+            let mut quotient = x[0].clone();
+            for divisor in x[1..].iter() {
+                quotient = numeric::div(&quotient, divisor)?;
+            }
+            return Ok(quotient);
         }),
-        // return function for multiplication (takes a list of integers and returns its product),
+        // return function for multiplication (takes a list of numbers and returns its product),
         // Needs at least one parameter
         "*" => RlType::Func(|a: Vec<RlType>| {
-            let x = check_int_vector(a)?;
-            if x.len() < 1 {return Err(error("* needs at least 1 parameter"));}
-            return Ok(RlType::Int(x.to_vec().iter().product()));
+            let x = check_number_vector(a)?;
+            check_arity("*", x.len(), 1, None)?;
+            let mut product = x[0].clone();
+            for factor in x[1..].iter() {
+                product = numeric::mul(&product, factor)?;
+            }
+            return Ok(product);
         }),
-        // return function for subtraction (takes a list of integers and returns value of first
+        // return function for subtraction (takes a list of numbers and returns value of first
         // element minus sum of the rest elements). Needs at least one parameter (with 1 arg arg is negated)
         "-" => RlType::Func(|a: Vec<RlType>| {
-            let x = check_int_vector(a)?;
-            if x.len() < 1 {
-                return Err(error("- needs at least 1 parameter"));
-            } else if x.len() == 1 {
-                return Ok(RlType::Int(- x[0]));
+            let x = check_number_vector(a)?;
+            check_arity("-", x.len(), 1, None)?;
+            if x.len() == 1 {
+                return numeric::sub(&RlType::Int(0), &x[0]);
             }
-            let neg: i64 = x[1..].to_vec().iter().sum();
-            Ok(RlType::Int(x[0] - neg))
+            let neg = sum(&x[1..])?;
+            numeric::sub(&x[0], &neg)
         }),
-        // return function, that checks if first integer is greater than the second one
+        // return functions for full number division of two integers
+        "quotient" => RlType::Func(|a: Vec<RlType>| {
+            check_arity("quotient", a.len(), 2, Some(2))?;
+            numeric::integer_division(&a[0], &a[1], "quotient")
+        }),
+        "remainder" => RlType::Func(|a: Vec<RlType>| {
+            check_arity("remainder", a.len(), 2, Some(2))?;
+            numeric::integer_division(&a[0], &a[1], "remainder")
+        }),
+        "modulo" => RlType::Func(|a: Vec<RlType>| {
+            check_arity("modulo", a.len(), 2, Some(2))?;
+            numeric::integer_division(&a[0], &a[1], "modulo")
+        }),
+        // return function, that checks if first number is greater than the second one
         ">" => RlType::Func(|a| {
-            let x = check_int_vector(a)?;
-            if x.len() != 2 {return Err(error("> needs exactly 2 args"));}
-            return Ok(RlType::Bool(x[0] > x[1]));
+            let x = check_number_vector(a)?;
+            check_arity(">", x.len(), 2, Some(2))?;
+            return Ok(RlType::Bool(numeric::compare(&x[0], &x[1])? == Some(Ordering::Greater)));
         }),
-        // return function, that checks if first integer is smaller than the second one
+        // return function, that checks if first number is smaller than the second one
         _ => RlType::Func(|a: Vec<RlType>| {
-            let x = check_int_vector(a)?;
-            if x.len() != 2 {return Err(error("> needs exactly 2 args"));}
-            return Ok(RlType::Bool(x[0] < x[1]));
+            let x = check_number_vector(a)?;
+            check_arity("<", x.len(), 2, Some(2))?;
+            return Ok(RlType::Bool(numeric::compare(&x[0], &x[1])? == Some(Ordering::Less)));
         }),
     }
 }
 
 /**
-    This function is a helper that is used by the Integer-Arithmetics to check if a List contains
-    just Integers. It takes a List of expressions, check if every expression is an Integer and
-    returns a vector of Integers in Best case. If the given list contains non-integers, return an Error.
+    This function is a helper that is used by the Arithmetics to check if a List contains
+    just numbers. It takes a List of expressions, check if every expression is a number and
+    returns the vector in Best case. If the given list contains non-numbers, return an Error.
 
-    Arguments:  vec - the list of expressions that should be validated to be Integers
-    Returns:    a vector containing i64 values extracted from the given list or an Error.
+    Arguments:  vec - the list of expressions that should be validated to be numbers
+    Returns:    the given vector if it just contains numbers or an Error.
 */
-fn check_int_vector(vec: Vec<RlType>) -> Result<Vec<i64>, RlErr> {
-    let mut new_vec = Vec::new();
+fn check_number_vector(vec: Vec<RlType>) -> Result<Vec<RlType>, RlErr> {
     for element in vec.iter() {
-        match element {
-            RlType::Int(i) => new_vec.push(i.clone()),
-            _ => return Err(error("Wrong kind of arguments!")),
+        if !numeric::is_number(element) {
+            return Err(type_error("a number", element));
         }
     }
-    return Ok(new_vec);
+    return Ok(vec);
+}
+
+/**
+    Helper for the Arithmetics that adds up a list of numbers.
+
+    Arguments:  numbers - the numbers to add up
+    Returns:    the sum of the numbers (Int 0 for an empty list) or an Error
+*/
+fn sum(numbers: &[RlType]) -> RlReturn {
+    let mut result = RlType::Int(0);
+    for summand in numbers.iter() {
+        result = numeric::add(&result, summand)?;
+    }
+    return Ok(result);
+}
+
+/**
+    Returns functions of type RlType::Func that perform mathematical functions on one number.
+    The specific function returned depends on the parameter. (Options: sqrt, exp, floor, round, numerator,
+    denominator, exact->inexact, truncate)
+
+    Arguments:  name - the name of the mathematical function
+    Returns:    a Function of type RlType::Func performing the mathematical function
+*/
+fn math_function(name: &str) -> RlType {
+    match name {
+        // square root, exact for Ints that are perfect squares
+        "sqrt" => RlType::Func(|a| {
+            check_arity("sqrt", a.len(), 1, Some(1))?;
+            numeric::sqrt(&a[0])
+        }),
+        // e to the power of the argument, result is always a Float
+        "exp" => RlType::Func(|a| {
+            check_arity("exp", a.len(), 1, Some(1))?;
+            Ok(RlType::Float(numeric::to_float(&a[0])?.exp()))
+        }),
+        // largest integral value not greater than the argument
+        "floor" => RlType::Func(|a| {
+            check_arity("floor", a.len(), 1, Some(1))?;
+            numeric::round_with(&a[0], f64::floor, BigRational::floor)
+        }),
+        // nearest integral value, rounds to even on .5
+        "round" => RlType::Func(|a| {
+            check_arity("round", a.len(), 1, Some(1))?;
+            numeric::round_with(&a[0], f64::round_ties_even, numeric::round_rational_ties_even)
+        }),
+        // numerator and denominator of the (exact value of the) argument
+        "numerator" => RlType::Func(|a| {
+            check_arity("numerator", a.len(), 1, Some(1))?;
+            numeric::numerator_denominator(&a[0], true)
+        }),
+        "denominator" => RlType::Func(|a| {
+            check_arity("denominator", a.len(), 1, Some(1))?;
+            numeric::numerator_denominator(&a[0], false)
+        }),
+        // converts the argument to a Float
+        "exact->inexact" => RlType::Func(|a| {
+            check_arity("exact->inexact", a.len(), 1, Some(1))?;
+            Ok(RlType::Float(numeric::to_float(&a[0])?))
+        }),
+        // integral part of the argument (rounds towards zero)
+        _ => RlType::Func(|a| {
+            check_arity("truncate", a.len(), 1, Some(1))?;
+            numeric::round_with(&a[0], f64::trunc, BigRational::trunc)
+        }),
+    }
+}
+
+/**
+    Returns functions of type RlType::Func that raise errors or inspect error objects (the values
+    that are bound in a catch clause for internal errors). The specific function returned depends on
+    the parameter. (Options: raise, error-object?, error-kind, error-message, error-payload)
+
+    Arguments:  name - the name of the function
+    Returns:    a Function of type RlType::Func
+*/
+fn error_function(name: &str) -> RlType {
+    match name {
+        // throws the given value, error objects are thrown as the error they hold
+        "raise" => RlType::Func(|a| {
+            check_arity("raise", a.len(), 1, Some(1))?;
+            Err(value_to_error(a[0].clone()))
+        }),
+        // checks if the argument is an error object
+        "error-object?" => RlType::Func(|a| {
+            check_arity("error-object?", a.len(), 1, Some(1))?;
+            Ok(RlType::Bool(matches!(a[0], RlType::Error(..))))
+        }),
+        // the kind of the error as symbol
+        "error-kind" => RlType::Func(|a| {
+            check_arity("error-kind", a.len(), 1, Some(1))?;
+            let kind = match get_error(&a[0])? {
+                RlErr::UnboundSymbol(_) => "unbound-symbol",
+                RlErr::Arity { .. } => "arity",
+                RlErr::Type { .. } => "type",
+                RlErr::DivByZero => "div-by-zero",
+                RlErr::Parse(_) | RlErr::Incomplete(_) => "parse",
+                RlErr::Io { .. } => "io",
+                RlErr::Syntax(_) => "syntax",
+                RlErr::State(_) => "state",
+                RlErr::User(_) => "user",
+                RlErr::Escape { .. } => "continuation",
+                // get_error removes the call frames
+                RlErr::Traced { .. } => unreachable!(),
+            };
+            Ok(RlType::Symbol(kind.to_string()))
+        }),
+        // the message that would have been printed for the error
+        "error-message" => RlType::Func(|a| {
+            check_arity("error-message", a.len(), 1, Some(1))?;
+            Ok(RlType::String(get_error(&a[0])?.to_string()))
+        }),
+        // the data the error carries (symbol name, offending value, ...)
+        _ => RlType::Func(|a| {
+            check_arity("error-payload", a.len(), 1, Some(1))?;
+            Ok(match get_error(&a[0])? {
+                RlErr::UnboundSymbol(name) => RlType::Symbol(name),
+                // (name given min max), max is #nil if there is no upper limit
+                RlErr::Arity { name, min, max, given } => list(vec![
+                    RlType::Symbol(name),
+                    RlType::Int(given as i64),
+                    RlType::Int(min as i64),
+                    max.map_or(RlType::Nil, |max| RlType::Int(max as i64)),
+                ]),
+                RlErr::Type { got, .. } => got,
+                RlErr::Io { filename, .. } => RlType::String(filename),
+                RlErr::User(value) | RlErr::Escape { value, .. } => value,
+                RlErr::DivByZero
+                | RlErr::Parse(_)
+                | RlErr::Incomplete(_)
+                | RlErr::Syntax(_)
+                | RlErr::State(_)
+                | RlErr::Traced { .. } => RlType::Nil,
+            })
+        }),
+    }
+}
+
+/**
+    Helper for the error functions that extracts the error of an error object (without the call
+    frames attached to it).
+
+    Arguments:  value - the value that should be an error object
+    Returns:    the error held by the error object or an Error if the value is no error object
+*/
+fn get_error(value: &RlType) -> Result<RlErr, RlErr> {
+    return match value {
+        RlType::Error(err) => Ok(untraced(err).clone()),
+        _ => Err(type_error("an error object", value)),
+    };
 }
 
 /**
     This function returns the Function(RLType::Func) that performs the "car" operation.
-    car returns the first element of a given list.
+    car returns the first part of a given pair, which is the first element of a list.
 
     Returns: The Function that performs the car-operation (Type RLType::Func)
 */
 fn car() -> RlType {
-    /// Function that performs car operation
-    return RlType::Func(|a| match &a[0] {
-        // check if given argument is a List
-        RlType::List(l) => {
-            // if list is empty return Error
-            return if l.len() < 1 {
-                Err(error("car needs a list of min len 1"))
-            } else {
-                //println!("{:?}", l);
-                // else return first element of the list
-                Ok(l.get(0).unwrap().clone())
-            }
-        }
-        // if argument of car is no list, return Error
-        //_a => Err(error(&*format!("car expects a list! but got {:?}", _a))),
-        // modified to return value back for ROL
-        _a => Ok(_a.clone()),
+    // Function that performs car operation
+    return RlType::Func(|a| {
+        check_arity("car", a.len(), 1, Some(1))?;
+        return match uncons(&a[0]) {
+            Some((car, _)) => Ok(car),
+            // if argument of car is no pair or an empty list, return Error
+            None => Err(type_error("a pair", &a[0])),
+        };
     });
 }
 
 /**
     This function returns the Function(RLType::Func) that performs the "cdr" operation.
-    cdr returns the second part(the rest) of a given pair.
+    cdr returns the second part of a given pair, which is the rest of a list.
 
     Returns: The Function that performs the cdr-operation (Type RLType::Func)
 */
 fn cdr() -> RlType {
-    /// Function that performs cdr operation
-    return RlType::Func(|a| match &a[0] {
-        // check if argument given to cdr is a List
-        RlType::List(l) => {
-            // if list is empty
-            return if l.len() < 1 {
-                Err(error("cdr needs a list with min len 2!"))
-            } else {
-                if l.len() == 1 {
-                    Ok(RlType::List(vec![]))
-                }
-                else if l.len() == 2{
-                    // else just return the list without the first element
-                    Ok(l[1].clone())
-                } else {
-                    Ok(RlType::List(l[1..].to_vec().clone()))
-                }
-            }
-        }
-        // if argument given to car is no list, return an Error
-        _ => Err(error("cdr expects a list!")),
+    // Function that performs cdr operation
+    return RlType::Func(|a| {
+        check_arity("cdr", a.len(), 1, Some(1))?;
+        return match uncons(&a[0]) {
+            Some((_, cdr)) => Ok(cdr),
+            // if argument of cdr is no pair or an empty list, return Error
+            None => Err(type_error("a pair", &a[0])),
+        };
     });
 }
 
 /**
-    This function returns the Function(RLType::Func) that performs the "list" operation.
-    List is used to build lists from given elements. Recursively build up pair structure of cons.
+    This function performs the "list" operation.
+    List is used to build lists from given elements: a chain of pairs ending with the empty list.
 
-    Returns: The Function that performs the list-operation (Type RLType::Func)
+    Arguments:  args - the elements of the list
+    Returns:    the built list
 */
 fn list(args: Vec<RlType>) -> RlType {
-    return if args.len() < 1 {
-        RlType::List(vec![])
-    } else {
-        // if list has two elements, create a List/Pair and returns it
-        RlType::List(vec![args[0].clone(), list(args[1..].to_vec().clone())])
-    }
+    return list_from_vec(args);
 }
 
 /**
     This function returns the Function(RLType::Func) that performs the "cons" operation.
-    cons is used to prepend an element to a list. If second element is not a list, create
-    following structure: (element2 (element2 ()))
+    cons creates a new pair of the two given arguments. If the second one is a list, the first one is
+    prepended to it, otherwise the result is a dotted pair: (cons 1 2) -> (1 . 2)
 
     Returns: The Function that performs the cons-operation (Type RLType::Func)
 */
 fn cons() -> RlType {
-    /// Function that performs the cons operation
+    // Function that performs the cons operation
     return RlType::Func(|a| {
-        // check if given list has 2 elements
-        return if a.len() != 2 {
-            Err(error("cons needs 2 arguments"))
-        } else {
-            return match &a[1] {
-                // check if second argument is a list -> must be for cons!
-                RlType::List(l) => Ok(RlType::List(vec![a[0].clone(), RlType::List(l.clone())])),
-                // if its not, pack the second element in a separate pair with tailing empty list
-                _ => Ok(RlType::List(vec![a[0].clone(), RlType::List(vec![a[1].clone(), RlType::List(vec![])])])),
+        check_arity("cons", a.len(), 2, Some(2))?;
+        return Ok(make_pair(a[0].clone(), a[1].clone()));
+    });
+}
+
+/**
+    Returns functions of type RlType::Func that change values in place: the parts of pairs and the
+    content of boxes. A box is a mutable reference to a value. The setters return the new value.
+    (Options: set-car!, set-cdr!, box, unbox, set-box!, box?)
+
+    Arguments:  name - the name of the function
+    Returns:    a Function of type RlType::Func
+*/
+fn mutation(name: &str) -> RlType {
+    return match name {
+        // (set-car! pair value) replaces the first part of a pair
+        "set-car!" => RlType::Func(|a| {
+            check_arity("set-car!", a.len(), 2, Some(2))?;
+            return match &a[0] {
+                RlType::Pair(car, _) => {
+                    car.replace(a[1].clone());
+                    Ok(a[1].clone())
+                }
+                other => Err(type_error("a pair", other)),
             };
-        };
+        }),
+        // (set-cdr! pair value) replaces the second part of a pair
+        "set-cdr!" => RlType::Func(|a| {
+            check_arity("set-cdr!", a.len(), 2, Some(2))?;
+            return match &a[0] {
+                RlType::Pair(_, cdr) => {
+                    cdr.replace(a[1].clone());
+                    Ok(a[1].clone())
+                }
+                other => Err(type_error("a pair", other)),
+            };
+        }),
+        // (box value) creates a new box holding the value
+        "box" => RlType::Func(|a| {
+            check_arity("box", a.len(), 1, Some(1))?;
+            Ok(RlType::Box(Rc::new(RefCell::new(a[0].clone()))))
+        }),
+        // (unbox box) returns the value in the box
+        "unbox" => RlType::Func(|a| {
+            check_arity("unbox", a.len(), 1, Some(1))?;
+            return match &a[0] {
+                RlType::Box(content) => Ok(content.borrow().clone()),
+                other => Err(type_error("a box", other)),
+            };
+        }),
+        // (set-box! box value) replaces the value in the box
+        "set-box!" => RlType::Func(|a| {
+            check_arity("set-box!", a.len(), 2, Some(2))?;
+            return match &a[0] {
+                RlType::Box(content) => {
+                    content.replace(a[1].clone());
+                    Ok(a[1].clone())
+                }
+                other => Err(type_error("a box", other)),
+            };
+        }),
+        // checks if the argument is a box
+        _ => RlType::Func(|a| {
+            check_arity("box?", a.len(), 1, Some(1))?;
+            Ok(RlType::Bool(matches!(a[0], RlType::Box(..))))
+        }),
+    };
+}
+
+/**
+    Collects the elements of a proper list for the list functions (and the primitives, see eval.rs)
+
+    Arguments:  value - the list to collect the elements from
+    Returns:    the elements of the list in order or an Error if value is no proper list
+*/
+pub(crate) fn list_elements(value: &RlType) -> Result<Vec<RlType>, RlErr> {
+    return list_to_vec(value).ok_or_else(|| type_error("a list", value));
+}
+
+/**
+    Takes the argument lists of map and for-each and collects the argument lists for the single
+    function calls: the i-th call gets the i-th elements of all lists, the shortest list determines
+    the number of calls.
+
+    Arguments:  lists - the lists given to map or for-each
+    Returns:    the arguments of each call or an Error if one of the arguments is no list
+*/
+pub(crate) fn zip_lists(lists: &[RlType]) -> Result<Vec<Vec<RlType>>, RlErr> {
+    let lists = lists.iter().map(list_elements).collect::<Result<Vec<_>, RlErr>>()?;
+    let calls = lists.iter().map(|l| l.len()).min().unwrap_or(0);
+    return Ok((0..calls).map(|i| lists.iter().map(|l| l[i].clone()).collect()).collect());
+}
+
+/**
+    Returns the builtin functions (RlType::Builtin) that work on lists. The higher order functions (map,
+    for-each, filter, remove, reduce, fold-left, fold-right and apply) are primitives of the evaluator
+    (see eval.rs). The number of arguments is checked by the builtin. (Options: append, reverse, length,
+    nth, last, range, iota)
+
+    Arguments:  name - the name of the list function
+    Returns:    a Function of type RlType::Builtin performing the list function
+*/
+fn list_function(name: &str) -> RlType {
+    return match name {
+        // (append list1 list2 ...) returns a list with the elements of all lists
+        "append" => builtin(name, 0, None, |a| {
+            let mut elements = vec![];
+            for l in a.iter() {
+                elements.extend(list_elements(l)?);
+            }
+            Ok(list(elements))
+        }),
+        "reverse" => builtin(name, 1, Some(1), |a| {
+            let mut elements = list_elements(&a[0])?;
+            elements.reverse();
+            Ok(list(elements))
+        }),
+        "length" => builtin(name, 1, Some(1), |a| Ok(RlType::Int(list_elements(&a[0])?.len() as i64))),
+        // (nth list n) returns the n-th element of the list (counting from 0)
+        "nth" => builtin(name, 2, Some(2), |a| {
+            let elements = list_elements(&a[0])?;
+            let index = i64::try_from(a[1].clone())?;
+            return match usize::try_from(index).ok().and_then(|i| elements.get(i)) {
+                Some(element) => Ok(element.clone()),
+                None => Err(type_error("an index within the list", &a[1])),
+            };
+        }),
+        "last" => builtin(name, 1, Some(1), |a| {
+            return match list_elements(&a[0])?.pop() {
+                Some(element) => Ok(element),
+                None => Err(type_error("a non-empty list", &a[0])),
+            };
+        }),
+        // (range end), (range start end) or (range start end step) returns the integers from start
+        // (default 0) up to end (exclusive) with the distance step (default 1)
+        "range" => builtin(name, 1, Some(3), |a| {
+            let bounds = a.iter().cloned().map(i64::try_from).collect::<Result<Vec<i64>, RlErr>>()?;
+            let (start, end, step) = match bounds[..] {
+                [end] => (0, end, 1),
+                [start, end] => (start, end, 1),
+                _ => (bounds[0], bounds[1], bounds[2]),
+            };
+            if step == 0 {
+                return Err(type_error("a step other than 0", &a[2]));
+            }
+            let mut numbers = vec![];
+            let mut next = Some(start);
+            // the range ends as well if the next number doesn't fit into an Int anymore
+            while let Some(number) = next.filter(|n| (step > 0 && *n < end) || (step < 0 && *n > end)) {
+                numbers.push(RlType::Int(number));
+                next = number.checked_add(step);
+            }
+            Ok(list(numbers))
+        }),
+        // (iota count), (iota count start) or (iota count start step) returns count numbers, beginning
+        // with start (default 0) with the distance step (default 1)
+        _ => builtin(name, 1, Some(3), |a| {
+            let count = usize::try_from(i64::try_from(a[0].clone())?)
+                .map_err(|_| type_error("a non-negative count", &a[0]))?;
+            let start = a.get(1).cloned().unwrap_or(RlType::Int(0));
+            let step = a.get(2).cloned().unwrap_or(RlType::Int(1));
+            check_number_vector(vec![start.clone(), step.clone()])?;
+            let mut numbers = vec![];
+            // the first number is start itself, so an exact start stays exact even with a Float step
+            for i in 0..count {
+                numbers.push(match i {
+                    0 => start.clone(),
+                    _ => numeric::add(&start, &numeric::mul(&RlType::Int(i as i64), &step)?)?,
+                });
+            }
+            Ok(list(numbers))
+        }),
+    };
+}
+
+/**
+    This function returns the Function(RLType::Func) for make-promise. Promises are created by delay and
+    cons-stream and forced by force (see eval.rs).
+    (make-promise value) returns a promise that is forced already, promises are returned unchanged
+
+    Returns: The Function that performs make-promise (Type RLType::Func)
+*/
+fn make_promise() -> RlType {
+    return RlType::Func(|a| {
+        check_arity("make-promise", a.len(), 1, Some(1))?;
+        Ok(match &a[0] {
+            RlType::Promise(_) => a[0].clone(),
+            other => RlType::Promise(Rc::new(RefCell::new(Promise::Forced(other.clone())))),
+        })
     });
 }
 
+/**
+    Returns the builtin functions (RlType::Builtin) for coroutines (see coroutine.rs). (Options:
+    coroutine, resume, yield, coroutine-done?, make-generator)
+
+    Arguments:  name - the name of the function
+    Returns:    a Function of type RlType::Builtin
+*/
+fn coroutine_function(name: &str) -> RlType {
+    return match name {
+        // (coroutine f) creates a coroutine that calls f when it is resumed the first time
+        "coroutine" => builtin_with_env(name, 1, Some(1), |a, env| {
+            Ok(RlType::Coroutine(Rc::new(Coroutine::new(a[0].clone(), env.clone()))))
+        }),
+        // (resume coroutine args ...) continues the coroutine until it yields or returns. The first resume
+        // passes the arguments to f, the following ones give the first argument to the waiting yield
+        "resume" => RlType::Primitive(Primitive::Resume),
+        // (yield value) suspends the innermost running coroutine, its resume returns the value (#nil if
+        // there is none)
+        "yield" => RlType::Primitive(Primitive::Yield(None)),
+        // (coroutine-done? coroutine) checks if the function of the coroutine has returned
+        "coroutine-done?" => builtin(name, 1, Some(1), |a| match &a[0] {
+            RlType::Coroutine(coroutine) => Ok(RlType::Bool(coroutine.done())),
+            other => Err(type_error("a coroutine", other)),
+        }),
+        // (make-generator f) returns a function without parameters. Each call continues f, which gets a yield
+        // that suspends this generator as argument, until it yields the next value. When f has returned, the
+        // calls return its result.
+        _ => builtin_with_env(name, 1, Some(1), |a, env| {
+            Ok(RlType::Primitive(Primitive::Generator(Rc::new(Coroutine::new(a[0].clone(), env.clone())))))
+        }),
+    };
+}
+
 /**
     This function returns the Function(RLType::Func) that performs the "eq?" operation.
     eq? returns if the two given arguments are equal (equality is defined in types.rs).
@@ -264,12 +706,12 @@ fn cons() -> RlType {
     Returns: The Function that performs the eq?-operation (Type RLType::Func)
 */
 fn equals() -> RlType {
-    /// Function that performs eq?-Operation
+    // Function that performs eq?-Operation
     return RlType::Func(|a| {
         // check if eq? has been given exactly 2 arguments
         return if a.len() != 2 {
             // return Error if number of arguments is incorrect
-            Err(error("eq? takes exactly 2 args"))
+            Err(RlErr::Arity { name: "eq?".to_string(), min: 2, max: Some(2), given: a.len() })
         } else {
             // if 2 arguments given, check equality and return result(of Type RLType::Bool)
             Ok(RlType::Bool(a[0] == a[1]))
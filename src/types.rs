@@ -4,10 +4,16 @@ types.rs: Holds the types of the Abstract-Syntax-Tree(AST) named RlType, the par
 */
 
 // load needed sibling-modules
+use crate::coroutine::Coroutine;
 use crate::env::RlEnv;
-use crate::types::RlErr::ErrString;
+use crate::eval::Continuation;
+use crate::printer::print_str;
 
 // load needed Rust-Functionality
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use std::cell::RefCell;
+use std::convert::TryFrom;
 use std::fmt;
 use std::rc::Rc;
 
@@ -21,54 +27,274 @@ pub type RlReturn = Result<RlType, RlErr>;
 #[derive(Debug, Clone)]
 pub enum RlType {
     Int(i64),
+    BigInt(Rc<BigInt>),
+    Rational(Rc<BigRational>),
+    Float(f64),
     Bool(bool),
     Symbol(String),
     String(String),
     Func(fn(Vec<RlType>) -> RlReturn),
+    Builtin(Rc<Builtin>),
+    // a builtin function that calls other functions, it is evaluated by the evaluator itself
+    Primitive(Primitive),
     SelfDefinedFunc {
         env: RlEnv,
         params: Rc<Vec<RlType>>,
         body: Rc<RlType>,
+        name: Option<String>,
     },
-    List(Vec<RlType>),
+    Macro {
+        env: RlEnv,
+        params: Rc<Vec<RlType>>,
+        body: Rc<RlType>,
+    },
+    // lists read from source code remember where they start, to report the location of errors
+    List(Vec<RlType>, Option<Rc<Location>>),
+    // a cons cell (car and cdr), lists built at runtime are chains of pairs ending with the empty list.
+    // Both parts can be changed with set-car! and set-cdr!
+    Pair(Rc<RefCell<RlType>>, Rc<RefCell<RlType>>),
+    // a mutable reference to a value, created by box
+    Box(Rc<RefCell<RlType>>),
+    // a continuation created by call/cc: the rest of the evaluation at the time call/cc was called
+    Continuation(Rc<Continuation>),
+    // a function that is evaluated step by step, created by coroutine
+    Coroutine(Rc<Coroutine>),
+    // a delayed expression, created by delay. It is evaluated by force the first time it is needed
+    Promise(Rc<RefCell<Promise>>),
+    Error(Rc<RlErr>),
     Nil,
 }
 
-/// A Type to define Errors
-#[derive(Debug)]
+/// A Type to define Errors. Every kind of error carries the data needed to describe it.
+#[derive(Debug, Clone)]
 pub enum RlErr {
-    // Defines Error-type String
-    ErrString(String),
+    // A symbol that is not defined in the environment was looked up
+    UnboundSymbol(String),
+    // A function or special form was given a wrong number of arguments. max is None if there is no
+    // upper limit for the number of arguments
+    Arity {
+        name: String,
+        min: usize,
+        max: Option<usize>,
+        given: usize,
+    },
+    // A value of the wrong type was given, expected describes what would have been valid
+    Type { expected: String, got: RlType },
+    // An exact number was divided by zero
+    DivByZero,
+    // The reader could not parse the input
+    Parse(String),
+    // The input ended before the last expression was complete (e.g. a list was not closed)
+    Incomplete(String),
+    // A file could not be read
+    Io { filename: String, message: String },
+    // An expression was used in a context where it is not allowed (e.g. unquote outside of quasiquote)
+    Syntax(String),
+    // A value was used in a state that doesn't allow it (e.g. a finished coroutine was resumed)
+    State(String),
+    // An error raised by the RLisp program itself, carrying an arbitrary value
+    User(RlType),
+    // Not an error: a continuation of an outer evaluation was called with a value. It unwinds the
+    // evaluation up to the outer one, which continues with the continuation (see eval.rs)
+    Escape { continuation: Rc<Continuation>, value: RlType },
+    // Another error together with the RLisp call frames that were active when it occurred
+    // (innermost frame first) and the location of the form whose evaluation failed
+    Traced {
+        error: Box<RlErr>,
+        trace: Vec<Frame>,
+        location: Option<Rc<Location>>,
+    },
+}
+
+/// The state of a promise: the expression and the environment to evaluate it in until it was forced,
+/// then the value of the expression (it is evaluated just once)
+#[derive(Debug, Clone)]
+pub enum Promise {
+    Delayed(RlType, RlEnv),
+    Forced(RlType),
+}
+
+/// A position in the source code: the name of the file (or "repl") and line and column (starting at 1)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Location {
+    pub file: Rc<str>,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// The signature of the Rust closures behind builtins: they get the evaluated arguments and the
+/// environment of the caller, which is needed to call back into the evaluator (see eval::apply)
+pub type NativeFn = dyn Fn(&[RlType], &RlEnv) -> RlReturn;
+
+/// A function defined in Rust that (unlike RlType::Func) can capture state. The number of arguments
+/// is checked before the function is called.
+pub struct Builtin {
+    pub name: String,
+    pub min_args: usize,
+    // None if there is no upper limit for the number of arguments
+    pub max_args: Option<usize>,
+    pub func: Rc<NativeFn>,
+}
+
+/// Implement the Debug trait by hand, since closures can't be printed
+impl fmt::Debug for Builtin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Builtin({})", self.name)
+    }
+}
+
+/// The builtin functions that call other functions or suspend the evaluation (RlType::Primitive). They
+/// are evaluated by the evaluator itself (see eval.rs), so the functions they call can capture and
+/// call continuations and yield.
+#[derive(Debug, Clone)]
+pub enum Primitive {
+    CallCc,
+    DynamicWind,
+    Apply,
+    Map,
+    ForEach,
+    Filter,
+    Remove,
+    Reduce,
+    FoldLeft,
+    FoldRight,
+    Force,
+    Resume,
+    // the yield of a generator is bound to its coroutine, the global yield (None) suspends the innermost
+    // coroutine
+    Yield(Option<Rc<Coroutine>>),
+    // the function returned by make-generator, it resumes the coroutine
+    Generator(Rc<Coroutine>),
+}
+
+impl Primitive {
+    /**
+        Gets the name of the primitive (used in error messages)
+
+        Returns:    the name
+    */
+    pub fn name(&self) -> &'static str {
+        return match self {
+            Primitive::CallCc => "call/cc",
+            Primitive::DynamicWind => "dynamic-wind",
+            Primitive::Apply => "apply",
+            Primitive::Map => "map",
+            Primitive::ForEach => "for-each",
+            Primitive::Filter => "filter",
+            Primitive::Remove => "remove",
+            Primitive::Reduce => "reduce",
+            Primitive::FoldLeft => "fold-left",
+            Primitive::FoldRight => "fold-right",
+            Primitive::Force => "force",
+            Primitive::Resume => "resume",
+            Primitive::Yield(_) => "yield",
+            Primitive::Generator(_) => "generator",
+        };
+    }
+
+    /**
+        Gets the number of arguments the primitive takes
+
+        Returns:    the minimal number and the maximal number (None if there is no upper limit)
+    */
+    pub fn arity(&self) -> (usize, Option<usize>) {
+        return match self {
+            Primitive::CallCc | Primitive::Force => (1, Some(1)),
+            Primitive::Resume => (1, None),
+            Primitive::Yield(_) => (0, Some(1)),
+            Primitive::Generator(_) => (0, Some(0)),
+            Primitive::Filter | Primitive::Remove => (2, Some(2)),
+            Primitive::Apply | Primitive::Map | Primitive::ForEach => (2, None),
+            Primitive::Reduce | Primitive::DynamicWind => (3, Some(3)),
+            Primitive::FoldLeft | Primitive::FoldRight => (3, None),
+        };
+    }
+}
+
+/// A call of a SelfDefinedFunc, as it is shown in the backtrace of an error
+#[derive(Debug, Clone)]
+pub struct Frame {
+    // the name the function was defined with, None for anonymous functions
+    pub name: Option<String>,
+    // the evaluated arguments of the call
+    pub args: Vec<RlType>,
+    // the location of the call in the source code, if known
+    pub location: Option<Rc<Location>>,
 }
 
 /**
-    Defines which of the types are an atom: Int, Symbol, String, Nil, Bool, Empty list.
+    Defines which of the types are an atom: Int, BigInt, Rational, Float, Symbol, String, Nil, Bool, Empty list.
     It takes an arbitrary expression and returns a Boolean whether given expression has atomic type.
 
     Arguments:  expr - expression of type RLType, that is to be checked
     Returns:    true if given type is atomic, false otherwise
 */
 pub fn is_atom(expr: RlType) -> bool {
-    match expr {
+    match &expr {
         RlType::Int(_i) => true,
+        RlType::BigInt(_i) => true,
+        RlType::Rational(_i) => true,
+        RlType::Float(_i) => true,
         RlType::Symbol(_i) => true,
         RlType::Nil => true,
         RlType::Bool(_i) => true,
         RlType::String(_i) => true,
-        //RlType::List(l) if l.len() == 0 => true,
+        //RlType::List(l, _) if l.len() == 0 => true,
         _ => false,
     }
 }
 
+/**
+    Defines which values count as true in conditionals (Scheme truthiness): every value except #f and
+    #nil, so 0, "" and the empty list are true as well.
+
+    Arguments:  value - the value of a test expression
+    Returns:    false for #f and #nil, true otherwise
+*/
+pub fn is_truthy(value: &RlType) -> bool {
+    return !matches!(value, RlType::Bool(false) | RlType::Nil);
+}
+
 /// Implement the display trait so that errors are shown nicely
 impl fmt::Display for RlErr {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            ErrString(i) => write!(f, "{}", i),
+            RlErr::UnboundSymbol(name) => write!(f, "Symbol {} not found", name),
+            RlErr::Arity { name, min, max, given } => {
+                // describe the valid number of arguments
+                let expected = match max {
+                    Some(max) if max == min => format!("exactly {}", min),
+                    Some(max) => format!("{} to {}", min, max),
+                    None => format!("at least {}", min),
+                };
+                write!(f, "{} takes {} argument(s), but got {}", name, expected, given)
+            }
+            RlErr::Type { expected, got } => {
+                write!(f, "Expected {}, but got {}", expected, print_str(got.clone()))
+            }
+            RlErr::DivByZero => write!(f, "cant divide by zero"),
+            RlErr::Parse(message) | RlErr::Incomplete(message) => write!(f, "Parse error: {}", message),
+            RlErr::Io { filename, message } => write!(f, "Could not read {}: {}", filename, message),
+            RlErr::Syntax(message) | RlErr::State(message) => write!(f, "{}", message),
+            RlErr::User(value) => write!(f, "{}", print_str(value.clone())),
+            RlErr::Escape { value, .. } => write!(f, "continuation called with {}", print_str(value.clone())),
+            RlErr::Traced {
+                error,
+                location: Some(location),
+                ..
+            } => write!(f, "{} (at {})", error, location),
+            RlErr::Traced { error, .. } => write!(f, "{}", error),
         }
     }
 }
 
+/// Implement the display trait so that locations are shown as file:line:column
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
 /// Implement the PartialEq trait for the RlType so that comparisons are possible. This is mostly used
 /// for the "eq?" functionality of RLisp
 impl PartialEq for RlType {
@@ -84,10 +310,21 @@ impl PartialEq for RlType {
     fn eq(&self, other: &RlType) -> bool {
         match (self, other) {
             (RlType::Int(ref a), RlType::Int(ref b)) => a == b,
+            (RlType::BigInt(ref a), RlType::BigInt(ref b)) => a == b,
+            (RlType::Rational(ref a), RlType::Rational(ref b)) => a == b,
+            (RlType::Float(ref a), RlType::Float(ref b)) => a == b,
             (RlType::Bool(ref a), RlType::Bool(ref b)) => a == b,
             (RlType::Symbol(ref a), RlType::Symbol(ref b)) => a == b,
             (RlType::Nil, RlType::Nil) => true,
-            (RlType::List(ref a), RlType::List(ref b)) => a == b,
+            (RlType::List(ref a, _), RlType::List(ref b, _)) => a == b,
+            (RlType::Pair(..), RlType::Pair(..)) => pairs_equal(self, other),
+            // boxes are just equal to themselves
+            (RlType::Box(ref a), RlType::Box(ref b)) => Rc::ptr_eq(a, b),
+            (RlType::Continuation(ref a), RlType::Continuation(ref b)) => Rc::ptr_eq(a, b),
+            (RlType::Coroutine(ref a), RlType::Coroutine(ref b)) => Rc::ptr_eq(a, b),
+            (RlType::Promise(ref a), RlType::Promise(ref b)) => Rc::ptr_eq(a, b),
+            // a list read from the source code is equal to a chain of pairs with the same elements
+            (RlType::List(..), RlType::Pair(..)) | (RlType::Pair(..), RlType::List(..)) => pairs_equal(self, other),
             (RlType::String(ref a), RlType::String(ref b)) => a == b,
             _ => false,
         }
@@ -95,11 +332,409 @@ impl PartialEq for RlType {
 }
 
 /**
-    Helper to create ErrString-Instance
+    Compares two lists of which at least one is built of pairs. The elements are compared one after
+    another and just the cars are compared recursively, so long lists don't overflow the stack.
+
+    Arguments:  a - the first list
+                b - the second list
+    Returns:    bool whether the lists are equal or not
+*/
+fn pairs_equal(a: &RlType, b: &RlType) -> bool {
+    let (mut a, mut b) = (a.clone(), b.clone());
+    loop {
+        // two lists read from source code are compared as a whole
+        if let (RlType::List(..), RlType::List(..)) = (&a, &b) {
+            return a == b;
+        }
+        match (uncons(&a), uncons(&b)) {
+            (Some((car_a, cdr_a)), Some((car_b, cdr_b))) => {
+                if car_a != car_b {
+                    return false;
+                }
+                a = cdr_a;
+                b = cdr_b;
+            }
+            // the ends of the lists (the empty list or the tail of a dotted list)
+            (None, None) => return a == b,
+            _ => return false,
+        }
+    }
+}
+
+impl Drop for RlType {
+    /**
+        Frees the cdrs of a chain of pairs one after another. Without it every cdr would be freed from
+        inside of the drop of its pair, so dropping a long list would overflow the stack. Cdrs that are
+        shared with other values are left alone.
+
+        Arguments:  self - the value that is dropped
+    */
+    fn drop(&mut self) {
+        let RlType::Pair(_, cdr) = self else {
+            return;
+        };
+        let mut rest = take_unique(cdr);
+        while let Some(RlType::Pair(_, cdr)) = &mut rest {
+            // the pair is dropped with the empty cdr that is left in it
+            rest = take_unique(cdr);
+        }
+    }
+}
+
+/**
+    Takes the value out of the cdr of a pair if no other value refers to the cdr, the cdr is left empty.
+
+    Arguments:  cdr - the cdr of a pair
+    Returns:    the value of the cdr or None if the cdr is shared
+*/
+fn take_unique(cdr: &mut Rc<RefCell<RlType>>) -> Option<RlType> {
+    return Rc::get_mut(cdr).map(|cell| std::mem::replace(cell.get_mut(), RlType::Nil));
+}
+
+/**
+    Creates a builtin function from a Rust closure
+
+    Arguments:  name - the name of the builtin (used in error messages)
+                min_args - the minimal number of arguments
+                max_args - the maximal number of arguments, None if there is no upper limit
+                func - the closure that is called with the evaluated arguments
+    Returns:    new RlType::Builtin Instance
+*/
+pub fn builtin(
+    name: &str,
+    min_args: usize,
+    max_args: Option<usize>,
+    func: impl Fn(&[RlType]) -> RlReturn + 'static,
+) -> RlType {
+    return builtin_with_env(name, min_args, max_args, move |args, _| func(args));
+}
+
+/**
+    Creates a builtin function from a Rust closure that gets the environment of the caller as well.
+    With it the closure can apply RLisp functions that are given as arguments (see eval::apply),
+    errors of these functions should be passed on with ?.
+
+    Arguments:  name - the name of the builtin (used in error messages)
+                min_args - the minimal number of arguments
+                max_args - the maximal number of arguments, None if there is no upper limit
+                func - the closure that is called with the evaluated arguments and the caller's environment
+    Returns:    new RlType::Builtin Instance
+*/
+pub fn builtin_with_env(
+    name: &str,
+    min_args: usize,
+    max_args: Option<usize>,
+    func: impl Fn(&[RlType], &RlEnv) -> RlReturn + 'static,
+) -> RlType {
+    return RlType::Builtin(Rc::new(Builtin {
+        name: name.to_string(),
+        min_args,
+        max_args,
+        func: Rc::new(func),
+    }));
+}
+
+/**
+    Helper to create a Type-Error
+
+    Arguments:  expected - description of what would have been a valid value
+                got - the offending value
+    Returns:    new RlErr::Type Instance
+*/
+pub fn type_error(expected: &str, got: &RlType) -> RlErr {
+    return RlErr::Type {
+        expected: expected.to_string(),
+        got: got.clone(),
+    };
+}
+
+/**
+    Checks whether a function or special form was given a valid number of arguments.
 
-    Arguments:  str - String that should be the errormessage
-    Returns:    new ErrString Instance
+    Arguments:  name - name of the function or special form (used in the error message)
+                given - the number of given arguments
+                min - the minimal number of arguments
+                max - the maximal number of arguments, None if there is no upper limit
+    Returns:    Ok if the number of arguments is valid, an RlErr::Arity otherwise
 */
-pub fn error(str: &str) -> RlErr {
-    return ErrString(String::from(str));
+pub fn check_arity(name: &str, given: usize, min: usize, max: Option<usize>) -> Result<(), RlErr> {
+    return if given < min || max.is_some_and(|max| given > max) {
+        Err(RlErr::Arity {
+            name: name.to_string(),
+            min,
+            max,
+            given,
+        })
+    } else {
+        Ok(())
+    };
+}
+
+/**
+    Converts an error to the value that is bound to the symbol of a catch clause. Values raised by
+    the RLisp program are given back unchanged, all other errors are wrapped as error objects.
+
+    Arguments:  err - the caught error
+    Returns:    the raised value or an RlType::Error holding the error
+*/
+pub fn error_to_value(err: RlErr) -> RlType {
+    return match untraced(&err) {
+        RlErr::User(value) => value.clone(),
+        _ => RlType::Error(Rc::new(err)),
+    };
+}
+
+/**
+    Converts a value to the error that is thrown by raise. Error objects are thrown again as the
+    error they are holding, all other values are thrown as RlErr::User.
+
+    Arguments:  value - the raised value
+    Returns:    the according RlErr
+*/
+pub fn value_to_error(value: RlType) -> RlErr {
+    return match &value {
+        RlType::Error(err) => (**err).clone(),
+        _ => RlErr::User(value),
+    };
+}
+
+/**
+    Attaches a location to an error that has no location attached yet.
+
+    Arguments:  err - the error
+                location - the location where the error occurred
+    Returns:    the error with a location attached
+*/
+pub fn locate(err: RlErr, location: Rc<Location>) -> RlErr {
+    return match err {
+        RlErr::Traced {
+            error,
+            trace,
+            location: None,
+        } => RlErr::Traced {
+            error,
+            trace,
+            location: Some(location),
+        },
+        RlErr::Traced { .. } => err,
+        _ => RlErr::Traced {
+            error: Box::new(err),
+            trace: vec![],
+            location: Some(location),
+        },
+    };
+}
+
+/**
+    Returns the error without the call frames that might be attached to it.
+
+    Arguments:  err - the error, possibly with attached call frames
+    Returns:    the error that actually occurred
+*/
+pub fn untraced(err: &RlErr) -> &RlErr {
+    return match err {
+        RlErr::Traced { error, .. } => untraced(error),
+        _ => err,
+    };
+}
+
+/// Conversions from Rust values to RLisp values, e.g. to define them in an Interpreter
+impl From<i64> for RlType {
+    fn from(value: i64) -> RlType {
+        return RlType::Int(value);
+    }
+}
+
+impl From<f64> for RlType {
+    fn from(value: f64) -> RlType {
+        return RlType::Float(value);
+    }
+}
+
+impl From<bool> for RlType {
+    fn from(value: bool) -> RlType {
+        return RlType::Bool(value);
+    }
+}
+
+impl From<&str> for RlType {
+    fn from(value: &str) -> RlType {
+        return RlType::String(value.to_string());
+    }
+}
+
+impl From<String> for RlType {
+    fn from(value: String) -> RlType {
+        return RlType::String(value);
+    }
+}
+
+/**
+    Creates a new pair (like the cons function of RLisp)
+
+    Arguments:  car - the first part of the pair
+                cdr - the second part of the pair, the rest of the list
+    Returns:    the new RlType::Pair
+*/
+pub fn make_pair(car: RlType, cdr: RlType) -> RlType {
+    return RlType::Pair(Rc::new(RefCell::new(car)), Rc::new(RefCell::new(cdr)));
+}
+
+/**
+    Builds a list of pairs from the given elements: (a b c) is (a . (b . (c . tail)))
+
+    Arguments:  elements - the elements of the list
+                tail - the cdr of the last pair, the empty list for a proper list
+    Returns:    the chain of pairs, or tail if there are no elements
+*/
+pub fn make_list(elements: Vec<RlType>, tail: RlType) -> RlType {
+    let mut list = tail;
+    for element in elements.into_iter().rev() {
+        list = make_pair(element, list);
+    }
+    return list;
+}
+
+/**
+    Builds a proper list of pairs from the given elements (like the list function of RLisp)
+
+    Arguments:  elements - the elements of the list
+    Returns:    the chain of pairs ending with the empty list
+*/
+pub fn list_from_vec(elements: Vec<RlType>) -> RlType {
+    return make_list(elements, RlType::List(vec![], None));
+}
+
+/**
+    Converts quoted data: the lists read from source code are rebuilt as chains of pairs, also inside of
+    nested lists and dotted lists, so they are taken apart by cdr without copying and can be changed by
+    set-car! and set-cdr! like lists built with list or cons. The empty list stays as it is.
+
+    Arguments:  expression - the quoted expression
+    Returns:    the expression with pairs instead of lists
+*/
+pub fn to_data(expression: &RlType) -> RlType {
+    return match expression {
+        RlType::List(l, _) if !l.is_empty() => list_from_vec(l.iter().map(to_data).collect()),
+        RlType::Pair(..) => {
+            // walk along the cdrs instead of recursing, a dotted list can be long
+            let mut elements = vec![];
+            let mut rest = expression.clone();
+            while let RlType::Pair(car, cdr) = &rest {
+                elements.push(to_data(&car.borrow()));
+                let next = cdr.borrow().clone();
+                rest = next;
+            }
+            make_list(elements, to_data(&rest))
+        }
+        _ => expression.clone(),
+    };
+}
+
+/**
+    Splits a pair or a non-empty list into its car and cdr. The cdr of a list read from source code (code
+    that is taken apart by a macro) is a copy of the list without its first element.
+
+    Arguments:  value - the pair or list to split
+    Returns:    car and cdr, None if value is no pair or an empty list
+*/
+pub fn uncons(value: &RlType) -> Option<(RlType, RlType)> {
+    return match value {
+        RlType::Pair(car, cdr) => Some((car.borrow().clone(), cdr.borrow().clone())),
+        RlType::List(l, _) if !l.is_empty() => Some((l[0].clone(), RlType::List(l[1..].to_vec(), None))),
+        _ => None,
+    };
+}
+
+/**
+    Collects the elements of a proper list, no matter if it was read from source code or built of pairs
+
+    Arguments:  value - the list to collect the elements from
+    Returns:    the elements in order, None if value is no proper list (e.g. (1 2 . 3))
+*/
+pub fn list_to_vec(value: &RlType) -> Option<Vec<RlType>> {
+    let mut elements = vec![];
+    let mut rest = value.clone();
+    loop {
+        match &rest {
+            RlType::Pair(car, cdr) => {
+                elements.push(car.borrow().clone());
+                let next = cdr.borrow().clone();
+                rest = next;
+            }
+            RlType::List(l, _) => {
+                elements.extend(l.iter().cloned());
+                return Some(elements);
+            }
+            _ => return None,
+        }
+    }
+}
+
+impl<T: Into<RlType>> From<Vec<T>> for RlType {
+    /// a Vec becomes a list of pairs, like the lists built by RLisp
+    fn from(value: Vec<T>) -> RlType {
+        return list_from_vec(value.into_iter().map(Into::into).collect());
+    }
+}
+
+impl<T: Into<RlType>> From<Option<T>> for RlType {
+    /// None becomes #nil
+    fn from(value: Option<T>) -> RlType {
+        return value.map_or(RlType::Nil, Into::into);
+    }
+}
+
+/// Conversions from RLisp values to Rust values, e.g. to use the results of an Interpreter.
+/// If the value has the wrong type, a Type-Error is returned.
+impl TryFrom<RlType> for i64 {
+    type Error = RlErr;
+
+    fn try_from(value: RlType) -> Result<i64, RlErr> {
+        return match value {
+            RlType::Int(i) => Ok(i),
+            _ => Err(type_error("an integer that fits into an i64", &value)),
+        };
+    }
+}
+
+impl TryFrom<RlType> for f64 {
+    type Error = RlErr;
+
+    /// all numbers can be converted to a f64
+    fn try_from(value: RlType) -> Result<f64, RlErr> {
+        return crate::numeric::to_float(&value);
+    }
+}
+
+impl TryFrom<RlType> for bool {
+    type Error = RlErr;
+
+    fn try_from(value: RlType) -> Result<bool, RlErr> {
+        return match value {
+            RlType::Bool(b) => Ok(b),
+            _ => Err(type_error("a boolean", &value)),
+        };
+    }
+}
+
+impl TryFrom<RlType> for String {
+    type Error = RlErr;
+
+    /// Strings and Symbols can be converted to a String
+    fn try_from(value: RlType) -> Result<String, RlErr> {
+        return match &value {
+            RlType::String(s) | RlType::Symbol(s) => Ok(s.to_string()),
+            _ => Err(type_error("a string", &value)),
+        };
+    }
+}
+
+impl TryFrom<RlType> for Vec<RlType> {
+    type Error = RlErr;
+
+    /// lists read from source code and proper lists of pairs can be converted to a Vec
+    fn try_from(value: RlType) -> Result<Vec<RlType>, RlErr> {
+        return list_to_vec(&value).ok_or_else(|| type_error("a list", &value));
+    }
 }
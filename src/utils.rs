@@ -17,3 +17,30 @@ pub fn string_is_integer(string: String) -> bool {
     // test if given string is a match and return according bool
     return re.is_match(&string);
 }
+
+/**
+    Takes a String and checks whether the string represents a Rational (like 3/4) using regex.
+
+    Arguments:  string - the string to check if it represents a Rational
+    Returns:    bool - whether the given string represents a Rational or not
+*/
+pub fn string_is_rational(string: String) -> bool {
+    // create new Regular expression that matches Rationals
+    let re = Regex::new(r"^-?[0-9]+/[0-9]+$").unwrap();
+    // test if given string is a match and return according bool
+    return re.is_match(&string);
+}
+
+/**
+    Takes a String and checks whether the string represents a Float using regex. Floats need a
+    decimal point or an exponent, e.g. 3.14, -0.5, .5, 1e10, -1e-3
+
+    Arguments:  string - the string to check if it represents a Float
+    Returns:    bool - whether the given string represents a Float or not
+*/
+pub fn string_is_float(string: String) -> bool {
+    // create new Regular expression that matches Floats
+    let re = Regex::new(r"^-?([0-9]+\.[0-9]*|\.[0-9]+|[0-9]+)([eE][-+]?[0-9]+)?$").unwrap();
+    // test if given string is a match and return according bool
+    return re.is_match(&string);
+}